@@ -0,0 +1,541 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use plotline::{
+    deref::With,
+    document::{lazy::LazyDocument, DocumentRepository, Revision},
+    graph::Graph,
+    id::Identify,
+    schema::{resource::Res, subscription::Subscription, Schema},
+};
+use plotline_plugin_search::{SearchIndex, SearchPlugin};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    document::Content,
+    link::{LinkIndex, LinkPlugin},
+    repository::LocalDocumentRepository,
+    tag::{TagIndex, TagPlugin},
+};
+
+/// The version of the cache format, to be increased on every incompatible change.
+const FORMAT: u32 = 2;
+
+/// The path of the cache file, relative to the base directory.
+const CACHE_PATH: &str = ".plotline/index";
+
+/// The files holding the ignore rules of the directory they are in.
+const IGNORE_FILES: [&str; 2] = [".gitignore", ".ignore"];
+
+type Node = LazyDocument<LocalDocumentRepository>;
+
+/// The state of a file by the time its document was indexed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct Stamp {
+    modified: SystemTime,
+    size: u64,
+}
+
+impl Stamp {
+    /// Returns the current stamp of the document with the given id, if its file exists.
+    fn of(document_repo: &LocalDocumentRepository, document_id: &PathBuf) -> Option<Self> {
+        match document_repo.revision(document_id)? {
+            Revision::Modified(modified, size) => Some(Self { modified, size }),
+            Revision::Hash(_) => None,
+        }
+    }
+
+    /// Returns the current stamp of the file or directory at the given path, if it exists.
+    fn at(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        Some(Self {
+            modified: metadata.modified().ok()?,
+            size: metadata.len(),
+        })
+    }
+}
+
+/// The content of the cache file.
+///
+/// Documents whose stamp is missing have been changed since they were indexed. Documents can
+/// only be added or removed along with the directory holding them or its ignore rules, so the
+/// ids of the documents are taken as they are as long as no walked path changed.
+#[derive(Default, Serialize, Deserialize)]
+struct Snapshot<Stamps, Tags, Search, Links> {
+    format: u32,
+    version: String,
+    extension: String,
+    /// The stamp of every walked directory and of the ignore files in them, if any.
+    walked: Stamps,
+    stamps: Stamps,
+    tags: Tags,
+    search: Search,
+    links: Links,
+}
+
+type OwnedSnapshot = Snapshot<
+    BTreeMap<PathBuf, Option<Stamp>>,
+    TagIndex<Node>,
+    SearchIndex<Node, Content>,
+    LinkIndex<Node>,
+>;
+
+/// A persistent cache of the documents of a [`LocalDocumentRepository`] and the indexes over
+/// them.
+///
+/// The base directory is walked only if any of the walked directories changed since the cache
+/// was saved, and documents are indexed only if their file changed, while the indexes of the
+/// rest are taken from the cache as they are.
+pub struct IndexCache {
+    /// The path of the cache file.
+    path: PathBuf,
+    /// The file's extension of the indexed documents.
+    extension: String,
+    /// The stamp of each walked path when the schema was opened.
+    walked: BTreeMap<PathBuf, Option<Stamp>>,
+    /// The stamp of each document when the schema was opened.
+    stamps: BTreeMap<PathBuf, Option<Stamp>>,
+    /// Whether the base directory was walked or any document reindexed when the schema was
+    /// opened, so the cache file is outdated.
+    outdated: bool,
+    /// The changes made to the schema since it was opened.
+    changes: Subscription<Node>,
+}
+
+impl IndexCache {
+    /// Returns the schema of all the documents in the given repository, with the tag, search and
+    /// link indexes installed, together with the cache they were taken from.
+    ///
+    /// A missing, corrupt or outdated cache is rebuilt from scratch.
+    pub fn open(document_repo: &Arc<LocalDocumentRepository>) -> Result<(Schema<Node>, Self)> {
+        let path = document_repo.context.join(CACHE_PATH);
+        let snapshot = match Self::read(&path) {
+            Ok(snapshot)
+                if snapshot.format == FORMAT
+                    && snapshot.version == env!("CARGO_PKG_VERSION")
+                    && snapshot.extension == document_repo.extension =>
+            {
+                Some(snapshot)
+            }
+            Ok(_) => {
+                tracing::info!("index cache is outdated, rebuilding");
+                None
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => {
+                tracing::warn!(error = err.to_string(), "reading index cache, rebuilding");
+                None
+            }
+        };
+
+        Self::build(document_repo, path, snapshot.unwrap_or_default())
+    }
+
+    /// Same as [`IndexCache::open`], but ignoring any existing cache.
+    pub fn rebuild(document_repo: &Arc<LocalDocumentRepository>) -> Result<(Schema<Node>, Self)> {
+        let path = document_repo.context.join(CACHE_PATH);
+        Self::build(document_repo, path, OwnedSnapshot::default())
+    }
+
+    /// Writes the indexes of the given schema into the cache file.
+    ///
+    /// The schema must be the one returned alongside this cache. Nothing is written unless the
+    /// base directory was walked or any document was reindexed, either when the schema was opened
+    /// or afterwards through the schema.
+    pub fn save(&self, schema: &Schema<Node>) -> Result<()> {
+        // Documents changed through the schema may differ from their files.
+        let changed: BTreeSet<_> = self
+            .changes
+            .try_iter()
+            .flat_map(|changeset| {
+                changeset
+                    .into_iter()
+                    .map(|op| op.id().clone())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        if !self.outdated && changed.is_empty() {
+            return Ok(());
+        }
+
+        let mut stamps = self.stamps.clone();
+        changed.into_iter().for_each(|document_id| {
+            stamps.insert(document_id, None);
+        });
+
+        let tags = Res::<TagIndex<Node>>::from(schema.resources());
+        let search = Res::<SearchIndex<Node, Content>>::from(schema.resources());
+        let links = Res::<LinkIndex<Node>>::from(schema.resources());
+        (tags, search, links)
+            .with(|(tags, search, links)| {
+                self.write(&Snapshot {
+                    format: FORMAT,
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                    extension: self.extension.clone(),
+                    walked: &self.walked,
+                    stamps: &stamps,
+                    tags,
+                    search,
+                    links,
+                })
+            })
+            .ok_or(anyhow::Error::msg("indexes must be installed"))?
+    }
+
+    fn build(
+        document_repo: &Arc<LocalDocumentRepository>,
+        path: PathBuf,
+        snapshot: OwnedSnapshot,
+    ) -> Result<(Schema<Node>, Self)> {
+        let OwnedSnapshot {
+            walked,
+            stamps: cached,
+            mut tags,
+            mut search,
+            mut links,
+            ..
+        } = snapshot;
+
+        let is_listed = !walked.is_empty()
+            && walked
+                .iter()
+                .all(|(path, stamp)| Stamp::at(&document_repo.context.join(path)) == *stamp);
+
+        let (document_ids, walked) = match is_listed {
+            true => (cached.keys().cloned().collect(), walked),
+            false => Self::scan(document_repo),
+        };
+
+        let mut stamps = BTreeMap::new();
+        let (unchanged, changed): (Vec<_>, Vec<_>) = document_ids
+            .into_iter()
+            .map(LazyDocument::builder(document_repo.clone()))
+            .partition(|document| {
+                let stamp = Stamp::of(document_repo, document.id());
+                stamps.insert(document.id().clone(), stamp);
+                stamp.is_some() && cached.get(document.id()) == Some(&stamp)
+            });
+
+        let unchanged_ids: BTreeSet<_> = unchanged.iter().map(|document| document.id()).collect();
+        let mut outdated = !is_listed || !changed.is_empty();
+        cached
+            .keys()
+            .filter(|document_id| !unchanged_ids.contains(document_id))
+            .for_each(|document_id| {
+                outdated = true;
+                tags.remove(document_id);
+                search.remove(document_id);
+                links.remove(document_id);
+            });
+
+        tracing::debug!(
+            listed = is_listed,
+            unchanged = unchanged.len(),
+            changed = changed.len(),
+            "opening index cache"
+        );
+
+        // Only the changed documents are indexed on install, the rest are added afterwards.
+        let schema = Schema::from(Graph::from_iter(changed))
+            .install(TagPlugin::from(tags).with_name("tags"))?
+            .install(SearchPlugin::from(search))?
+            .install(LinkPlugin::from(links).with_name("links"))?;
+
+        {
+            let mut graph = schema.write();
+            unchanged.into_iter().for_each(|document| {
+                graph.insert(document);
+            });
+        }
+
+        let cache = Self {
+            path,
+            extension: document_repo.extension.clone(),
+            walked,
+            stamps,
+            outdated,
+            changes: schema.subscribe(),
+        };
+
+        Ok((schema, cache))
+    }
+
+    /// Walks the base directory, returning the ids of all the documents together with the stamp
+    /// of every walked path.
+    ///
+    /// Paths changed while being walked are left unstamped, so they are walked again next time.
+    fn scan(
+        document_repo: &LocalDocumentRepository,
+    ) -> (Vec<PathBuf>, BTreeMap<PathBuf, Option<Stamp>>) {
+        let start = SystemTime::now();
+        let (document_ids, directories) = document_repo.scan();
+        let walked = directories
+            .into_iter()
+            .flat_map(|directory| {
+                let ignore_files: Vec<_> = IGNORE_FILES
+                    .iter()
+                    .map(|name| directory.join(name))
+                    .collect();
+
+                ignore_files.into_iter().chain([directory])
+            })
+            .map(|path| {
+                let stamp = Stamp::at(&document_repo.context.join(&path))
+                    .filter(|stamp| stamp.modified < start);
+                (path, stamp)
+            })
+            .collect();
+
+        (document_ids, walked)
+    }
+
+    fn read(path: &Path) -> io::Result<OwnedSnapshot> {
+        let file = fs::File::open(path)?;
+        Ok(serde_json::from_reader(BufReader::new(file))?)
+    }
+
+    /// Writes the given snapshot into a temporary file which then replaces the cache file, so
+    /// the cache is never left half-written.
+    fn write<S: Serialize>(&self, snapshot: &S) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let tmp = self.path.with_extension("tmp");
+        let mut writer = BufWriter::new(fs::File::create(&tmp)?);
+        serde_json::to_writer(&mut writer, snapshot)?;
+        writer.flush()?;
+        drop(writer);
+
+        fs::rename(tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        path::Path,
+        sync::Arc,
+        time::{Duration, SystemTime},
+    };
+
+    use plotline::{
+        deref::With,
+        schema::{ops::save::Save, resource::Res, Schema},
+    };
+    use plotline_plugin_search::{Query, SearchIndex};
+
+    use crate::{
+        document::{Content, Document},
+        link::{LinkIndex, Target},
+        repository::LocalDocumentRepository,
+        tag::{Tag, TagIndex},
+    };
+
+    use super::{IndexCache, Node, CACHE_PATH};
+
+    fn tagged(schema: &Schema<Node>, tag: &str) -> Vec<String> {
+        Res::<TagIndex<Node>>::from(schema.resources())
+            .with(|index| {
+                index
+                    .get(&Tag::parse(tag).unwrap())
+                    .map(|id| id.to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap()
+    }
+
+    fn found(schema: &Schema<Node>, query: &str) -> Vec<String> {
+        Res::<SearchIndex<Node, Content>>::from(schema.resources())
+            .with(|index| {
+                index
+                    .search(&Query::parse(query))
+                    .into_iter()
+                    .map(|hit| hit.id.to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap()
+    }
+
+    fn linking(schema: &Schema<Node>, id: &str) -> Vec<String> {
+        Res::<LinkIndex<Node>>::from(schema.resources())
+            .with(|index| {
+                index
+                    .get(&Target(id.into()))
+                    .map(|id| id.to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap()
+    }
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    /// Writes the given content without changing the size nor the modification time of the
+    /// file, so the change goes unnoticed by the cache.
+    fn overwrite(path: &Path, content: &str) {
+        let modified = fs::metadata(path).unwrap().modified().unwrap();
+        fs::write(path, content).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    #[test]
+    fn only_changed_documents_should_be_indexed() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = dir.path().to_path_buf();
+        write(&context.join("alice.md"), "Alice, #hero");
+        write(&context.join("bob.md"), "Bob, #character");
+        write(&context.join("city.md"), "A #place");
+
+        let document_repo = Arc::new(LocalDocumentRepository {
+            context: context.clone(),
+            extension: "md".into(),
+        });
+
+        let (schema, cache) = IndexCache::open(&document_repo).unwrap();
+        assert_eq!(tagged(&schema, "hero"), vec!["alice"]);
+        cache.save(&schema).unwrap();
+
+        // Alice is overwritten unnoticed, Bob is changed and the city is removed.
+        overwrite(&context.join("alice.md"), "Alice, #lost");
+        write(&context.join("bob.md"), "Bob, a #villain");
+        fs::remove_file(context.join("city.md")).unwrap();
+
+        let (schema, cache) = IndexCache::open(&document_repo).unwrap();
+        assert_eq!(
+            tagged(&schema, "hero"),
+            vec!["alice"],
+            "unchanged documents should not be reindexed"
+        );
+        assert_eq!(tagged(&schema, "villain"), vec!["bob"]);
+        assert!(tagged(&schema, "character").is_empty());
+        assert!(tagged(&schema, "place").is_empty());
+        assert_eq!(found(&schema, "alice"), vec!["alice"]);
+        assert_eq!(schema.read().len(), 2);
+
+        // Documents saved through the schema may differ from their files.
+        Save::new(Node::new(
+            document_repo.clone(),
+            Document::new("alice".into(), "md".into(), b"Alice, #saved".to_vec()),
+        ))
+        .execute(schema.transaction())
+        .unwrap();
+        cache.save(&schema).unwrap();
+
+        let (schema, _) = IndexCache::open(&document_repo).unwrap();
+        assert_eq!(
+            tagged(&schema, "lost"),
+            vec!["alice"],
+            "saved documents should be reindexed"
+        );
+        assert!(tagged(&schema, "saved").is_empty());
+
+        // Corrupt caches are rebuilt.
+        overwrite(&context.join("alice.md"), "Alice, #hero");
+        fs::write(context.join(CACHE_PATH), "corrupt").unwrap();
+        let (schema, _) = IndexCache::open(&document_repo).unwrap();
+        assert_eq!(tagged(&schema, "hero"), vec!["alice"]);
+        assert_eq!(tagged(&schema, "villain"), vec!["bob"]);
+    }
+
+    #[test]
+    fn unchanged_directories_should_not_be_walked() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = dir.path().to_path_buf();
+        write(&context.join(".ignore"), "secret.md\n");
+        write(&context.join("alice.md"), "Friend of [[bob]]");
+        write(&context.join("bob.md"), "Bob");
+
+        let document_repo = Arc::new(LocalDocumentRepository {
+            context: context.clone(),
+            extension: "md".into(),
+        });
+
+        // The cache directory is created on the first save, changing the base directory.
+        for _ in 0..2 {
+            let (schema, cache) = IndexCache::open(&document_repo).unwrap();
+            cache.save(&schema).unwrap();
+        }
+
+        // Carol is added unnoticed, since the modification time of the directory is kept.
+        let modified = fs::metadata(&context).unwrap().modified().unwrap();
+        write(&context.join("carol.md"), "Sister of [[bob]]");
+        fs::File::open(&context)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+
+        let (schema, cache) = IndexCache::open(&document_repo).unwrap();
+        assert_eq!(
+            schema.read().len(),
+            2,
+            "unchanged directories should not be walked"
+        );
+        assert_eq!(linking(&schema, "bob"), vec!["alice"]);
+        cache.save(&schema).unwrap();
+
+        // Changing the ignore rules changes what is walked.
+        write(&context.join(".ignore"), "alice.md\n");
+        let (schema, _) = IndexCache::open(&document_repo).unwrap();
+        assert_eq!(schema.read().len(), 2);
+        assert_eq!(linking(&schema, "bob"), vec!["carol"]);
+    }
+
+    #[test]
+    fn unchanged_caches_should_not_be_rewritten() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = dir.path().to_path_buf();
+        write(&context.join("alice.md"), "Alice");
+
+        let document_repo = Arc::new(LocalDocumentRepository {
+            context: context.clone(),
+            extension: "md".into(),
+        });
+
+        // The cache directory is created on the first save, changing the base directory.
+        for _ in 0..2 {
+            let (schema, cache) = IndexCache::open(&document_repo).unwrap();
+            cache.save(&schema).unwrap();
+        }
+
+        let path = context.join(CACHE_PATH);
+        let written = SystemTime::UNIX_EPOCH + Duration::from_secs(1);
+        let modified = || fs::metadata(&path).unwrap().modified().unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(written)
+            .unwrap();
+
+        let (schema, cache) = IndexCache::open(&document_repo).unwrap();
+        cache.save(&schema).unwrap();
+        assert_eq!(
+            modified(),
+            written,
+            "unchanged caches should not be rewritten"
+        );
+
+        write(&context.join("alice.md"), "Alice, #hero");
+        let (schema, cache) = IndexCache::open(&document_repo).unwrap();
+        cache.save(&schema).unwrap();
+        assert_ne!(modified(), written, "reindexed documents should be saved");
+    }
+}
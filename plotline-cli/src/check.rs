@@ -0,0 +1,509 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env, fs,
+    io::{self, Write},
+    marker::PhantomData,
+    path::PathBuf,
+    sync::Arc,
+};
+
+use plotline::{
+    deref::TryDeref,
+    document::{lazy::LazyDocument, DocumentRepository},
+    graph::Graph,
+    id::Identify,
+    property::Property,
+    schema::{
+        ops::save::Save,
+        plugin::Plugin,
+        rule::{Rule, Severity, Violation},
+        Schema,
+    },
+};
+use anyhow::Result;
+use clap::Args;
+use serde::Serialize;
+
+use crate::{
+    document::Document,
+    link::Link,
+    metadata::{Alias, Metadata, Parent, Title},
+    output::{Output, Record},
+    repository::Locate,
+};
+
+type Node<DocumentRepo> = LazyDocument<DocumentRepo>;
+
+/// Links must point to existing documents.
+///
+/// Fixed by creating an empty document for every missing one.
+pub struct DanglingLinks<DocumentRepo> {
+    document_repo: Arc<DocumentRepo>,
+}
+
+impl<DocumentRepo> Rule<Node<DocumentRepo>> for DanglingLinks<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    fn name(&self) -> &'static str {
+        "dangling-link"
+    }
+
+    fn check(&self, graph: &Graph<Node<DocumentRepo>>) -> Vec<Violation<PathBuf>> {
+        graph
+            .into_iter()
+            .filter_map(|document| document.try_deref())
+            .flat_map(|document| {
+                Link::occurrences(document)
+                    .into_iter()
+                    .filter(|occurrence| graph.node(occurrence.link.id.clone()).is_virtual())
+                    .map(|occurrence| {
+                        Violation::error(
+                            document.path.clone(),
+                            format!("document {:?} does not exist", occurrence.link.id),
+                        )
+                        .with_line(occurrence.line)
+                        .with_fix()
+                    })
+            })
+            .collect()
+    }
+
+    fn fix(
+        &self,
+        graph: &Graph<Node<DocumentRepo>>,
+        violation: &Violation<PathBuf>,
+    ) -> Option<Vec<Node<DocumentRepo>>> {
+        let node = graph.node(violation.id.clone());
+        let document = node.try_deref()?.try_deref()?;
+        let missing: BTreeSet<_> = Link::occurrences(document)
+            .into_iter()
+            .filter(|occurrence| Some(occurrence.line) == violation.line)
+            .map(|occurrence| occurrence.link.id)
+            .filter(|id| graph.node(id.clone()).is_virtual())
+            .collect();
+
+        let stubs = missing.into_iter().map(|id| {
+            let name = id.file_name().unwrap_or_default().to_string_lossy();
+            let bytes = format!("# {name}\n").into_bytes();
+            LazyDocument::new(
+                self.document_repo.clone(),
+                Document::new(id.clone(), document.extension.clone(), bytes),
+            )
+        });
+
+        Some(stubs.collect())
+    }
+}
+
+/// Documents should be linked from some other document.
+pub struct OrphanDocuments<DocumentRepo>(PhantomData<fn() -> DocumentRepo>);
+
+impl<DocumentRepo> Rule<Node<DocumentRepo>> for OrphanDocuments<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    fn name(&self) -> &'static str {
+        "orphan"
+    }
+
+    fn check(&self, graph: &Graph<Node<DocumentRepo>>) -> Vec<Violation<PathBuf>> {
+        let linked: BTreeSet<_> = graph
+            .into_iter()
+            .flat_map(|document| {
+                Link::all(document)
+                    .into_iter()
+                    .map(|link| link.id)
+                    .filter(|id| id != document.id())
+            })
+            .collect();
+
+        graph
+            .into_iter()
+            .filter(|document| !linked.contains(document.id()))
+            .map(|document| Violation::warning(document.id().clone(), "no document links here"))
+            .collect()
+    }
+}
+
+/// Titles and aliases should not be shared by different documents.
+pub struct DuplicateNames<DocumentRepo>(PhantomData<fn() -> DocumentRepo>);
+
+impl<DocumentRepo> Rule<Node<DocumentRepo>> for DuplicateNames<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    fn name(&self) -> &'static str {
+        "duplicate-name"
+    }
+
+    fn check(&self, graph: &Graph<Node<DocumentRepo>>) -> Vec<Violation<PathBuf>> {
+        // Names are compared case-insensitively, as wikilinks by name usually are.
+        let mut names: BTreeMap<String, BTreeSet<&PathBuf>> = BTreeMap::new();
+        graph.into_iter().for_each(|document| {
+            let titles = Title::all(document).into_iter().map(|title| title.0);
+            let aliases = Alias::all(document).into_iter().map(|alias| alias.0);
+            titles.chain(aliases).for_each(|name| {
+                names
+                    .entry(name.to_lowercase())
+                    .or_default()
+                    .insert(document.id());
+            });
+        });
+
+        names
+            .into_iter()
+            .filter(|(_, ids)| ids.len() > 1)
+            .flat_map(|(name, ids)| {
+                ids.iter()
+                    .map(|id| {
+                        let others: Vec<_> = ids.iter().filter(|other| *other != id).collect();
+                        Violation::warning(
+                            (*id).clone(),
+                            format!("name {name:?} is also used by {others:?}"),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+/// Front matter must be closed, well-formed and a map.
+pub struct MalformedFrontMatter<DocumentRepo>(PhantomData<fn() -> DocumentRepo>);
+
+impl<DocumentRepo> Rule<Node<DocumentRepo>> for MalformedFrontMatter<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    fn name(&self) -> &'static str {
+        "front-matter"
+    }
+
+    fn check(&self, graph: &Graph<Node<DocumentRepo>>) -> Vec<Violation<PathBuf>> {
+        graph
+            .into_iter()
+            .filter_map(|document| document.try_deref())
+            .filter_map(|document| {
+                let err = Metadata::try_parse(&String::from_utf8_lossy(&document.bytes)).err()?;
+                Some(Violation::error(document.path.clone(), err).with_line(1))
+            })
+            .collect()
+    }
+}
+
+/// A document must not be its own ancestor.
+pub struct ParentCycles<DocumentRepo>(PhantomData<fn() -> DocumentRepo>);
+
+impl<DocumentRepo> Rule<Node<DocumentRepo>> for ParentCycles<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    fn name(&self) -> &'static str {
+        "parent-cycle"
+    }
+
+    fn check(&self, graph: &Graph<Node<DocumentRepo>>) -> Vec<Violation<PathBuf>> {
+        let parents: BTreeMap<&PathBuf, Vec<PathBuf>> = graph
+            .into_iter()
+            .map(|document| {
+                let parents = Parent::all(document)
+                    .into_iter()
+                    .map(|parent| PathBuf::from(parent.0))
+                    .collect();
+
+                (document.id(), parents)
+            })
+            .collect();
+
+        // Depth-first search, where a parent already in the path closes a cycle.
+        let mut done = BTreeSet::new();
+        let mut violations = Vec::new();
+        for start in parents.keys() {
+            let mut path: Vec<&PathBuf> = Vec::new();
+            let mut stack = vec![(*start, 0)];
+            while let Some((id, next)) = stack.pop() {
+                if next == 0 {
+                    if done.contains(id) {
+                        continue;
+                    }
+
+                    path.push(id);
+                }
+
+                let Some(parent) = parents.get(id).and_then(|parents| parents.get(next)) else {
+                    done.insert(id);
+                    path.pop();
+                    continue;
+                };
+
+                stack.push((id, next + 1));
+                if let Some(position) = path.iter().position(|ancestor| *ancestor == parent) {
+                    let cycle: Vec<_> = path[position..].iter().chain([&parent]).collect();
+                    violations.push(Violation::error(
+                        parent.clone(),
+                        format!("document is its own ancestor: {cycle:?}"),
+                    ));
+                } else if parents.contains_key(parent) {
+                    stack.push((parent, 0));
+                }
+            }
+        }
+
+        violations
+    }
+}
+
+/// Registers the rules checked by `plot check`.
+pub struct CheckPlugin<DocumentRepo> {
+    pub document_repo: Arc<DocumentRepo>,
+}
+
+impl<DocumentRepo> Plugin<Node<DocumentRepo>> for CheckPlugin<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document> + Send + Sync,
+{
+    fn name(&self) -> &'static str {
+        "check"
+    }
+
+    fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    fn install(self, schema: Schema<Node<DocumentRepo>>) -> Schema<Node<DocumentRepo>> {
+        schema
+            .with_rule(DanglingLinks {
+                document_repo: self.document_repo,
+            })
+            .with_rule(OrphanDocuments(PhantomData))
+            .with_rule(DuplicateNames(PhantomData))
+            .with_rule(MalformedFrontMatter(PhantomData))
+            .with_rule(ParentCycles(PhantomData))
+    }
+}
+
+/// Check the documents against every rule in the schema.
+#[derive(Args)]
+pub struct CheckCommand {
+    /// Fix the violations that can be fixed automatically.
+    #[arg(long)]
+    fix: bool,
+}
+
+/// A violation of a rule, as reported by the CLI.
+#[derive(Debug, Clone, Serialize)]
+pub struct ViolationRecord {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub id: PathBuf,
+    /// The file holding the document.
+    pub path: PathBuf,
+    pub line: Option<usize>,
+    pub message: String,
+    pub fixable: bool,
+    /// Whether the violation got fixed.
+    pub fixed: bool,
+}
+
+impl Record for ViolationRecord {
+    fn write_text(&self, writer: &mut dyn Write) -> io::Result<()> {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+
+        write!(writer, "{}", self.path.display())?;
+        if let Some(line) = self.line {
+            write!(writer, ":{line}")?;
+        }
+
+        write!(writer, ": {severity}[{}]: {}", self.rule, self.message)?;
+        if self.fixed {
+            write!(writer, " (fixed)")?;
+        } else if self.fixable {
+            write!(writer, " (fixable)")?;
+        }
+
+        writeln!(writer)
+    }
+}
+
+pub struct CheckCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+    pub document_repo: Arc<DocumentRepo>,
+    pub output: Output,
+}
+
+impl<DocumentRepo> CheckCli<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document> + Locate,
+{
+    /// Reports every violation, returning true if, and only if, none of them is an error left
+    /// unfixed.
+    pub fn execute(&self, command: CheckCommand) -> Result<bool> {
+        let mut violations = self.schema.rules().check(&self.schema.snapshot());
+        violations.sort_by(|a, b| (&a.id, a.line).cmp(&(&b.id, b.line)));
+
+        let mut records = Vec::with_capacity(violations.len());
+        for violation in violations {
+            let fixed = command.fix && violation.fixable && self.fix(&violation)?;
+            records.push(ViolationRecord {
+                rule: violation.rule,
+                severity: violation.severity,
+                path: self.path(&violation.id),
+                id: violation.id,
+                line: violation.line,
+                message: violation.message,
+                fixable: violation.fixable,
+                fixed,
+            });
+        }
+
+        let unfixed = |severity| {
+            records
+                .iter()
+                .filter(|record| record.severity == severity && !record.fixed)
+                .count()
+        };
+
+        let (errors, warnings) = (unfixed(Severity::Error), unfixed(Severity::Warning));
+        tracing::info!(errors, warnings, "checked documents");
+
+        self.output.write(records)?;
+        Ok(errors == 0)
+    }
+
+    /// Saves and writes down the documents fixing the given violation, returning true if, and
+    /// only if, the violation got fixed.
+    ///
+    /// The fix is computed against the current state of the schema, so fixes applied before
+    /// are taken into account.
+    fn fix(&self, violation: &Violation<PathBuf>) -> Result<bool> {
+        let Some(documents) = self.schema.rules().fix(&self.schema.snapshot(), violation) else {
+            return Ok(false);
+        };
+
+        for document in documents {
+            let Some(bytes) = document.try_deref().map(|document| document.bytes.clone()) else {
+                continue;
+            };
+
+            let path = self.document_repo.locate(document.id());
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            Save::new(document).execute(self.schema.transaction())?;
+            fs::write(path, bytes)?;
+        }
+
+        Ok(true)
+    }
+
+    /// Returns the path of the file holding the given document, relative to the working
+    /// directory if possible.
+    fn path(&self, document_id: &PathBuf) -> PathBuf {
+        let path = self.document_repo.locate(document_id);
+        env::current_dir()
+            .ok()
+            .and_then(|dir| path.strip_prefix(dir).ok().map(ToOwned::to_owned))
+            .unwrap_or(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path, sync::Arc};
+
+    use plotline::{graph::Graph, schema::Schema};
+
+    use crate::{output::Output, repository::LocalDocumentRepository};
+
+    use super::{CheckCli, CheckCommand, CheckPlugin};
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn cli(context: &Path) -> CheckCli<LocalDocumentRepository> {
+        let document_repo = Arc::new(LocalDocumentRepository {
+            context: context.to_path_buf(),
+            extension: "md".into(),
+        });
+
+        let schema = Schema::from(Graph::from_iter(document_repo.all()))
+            .install(CheckPlugin {
+                document_repo: document_repo.clone(),
+            })
+            .unwrap();
+
+        CheckCli {
+            schema: Arc::new(schema),
+            document_repo,
+            output: Output {
+                quiet: true,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn violations(cli: &CheckCli<LocalDocumentRepository>) -> Vec<(&'static str, String)> {
+        let mut violations: Vec<_> = cli
+            .schema
+            .rules()
+            .check(&cli.schema.snapshot())
+            .into_iter()
+            .map(|violation| (violation.rule, violation.id.to_string_lossy().to_string()))
+            .collect();
+
+        violations.sort();
+        violations
+    }
+
+    #[test]
+    fn violations_should_be_found_and_fixed() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = dir.path();
+        write(
+            &context.join("alice.md"),
+            "---\ntitle: Alice\nparent: bob\n---\nSister of [[bob]].\nSee [[carol]].",
+        );
+        write(
+            &context.join("bob.md"),
+            "---\ntitle: Bob\naliases: [alice]\nparent: alice\n---\n[[alice]]",
+        );
+        write(
+            &context.join("dave.md"),
+            "---\ntitle: [Dave\n---\n[[alice]]",
+        );
+
+        let cli = cli(context);
+        assert_eq!(
+            violations(&cli),
+            vec![
+                ("dangling-link", "alice".into()),
+                ("duplicate-name", "alice".into()),
+                ("duplicate-name", "bob".into()),
+                ("front-matter", "dave".into()),
+                ("orphan", "dave".into()),
+                ("parent-cycle", "alice".into()),
+            ]
+        );
+
+        let passed = cli.execute(CheckCommand { fix: true }).unwrap();
+        assert!(!passed, "unfixed errors should fail the check");
+        assert_eq!(
+            fs::read_to_string(context.join("carol.md")).unwrap(),
+            "# carol\n",
+            "dangling links should be fixed with new documents"
+        );
+
+        assert!(!violations(&cli).contains(&("dangling-link", "alice".into())));
+        assert!(violations(&cli).contains(&("orphan", "dave".into())));
+    }
+}
@@ -1,15 +1,18 @@
 use std::{
-    error::Error,
-    fmt::Debug,
-    io::{self, Write},
+    fmt::{Debug, Display},
+    fs,
+    io::{self, Read, Write},
     path::PathBuf,
-    str::FromStr,
-    sync::Arc,
+    process::Command,
+    sync::{Arc, OnceLock},
 };
 
 use plotline::{
+    deref::TryDeref,
     document::{lazy::LazyDocument, DocumentRepository},
+    graph::Source,
     id::Identify,
+    property::Property,
     schema::{
         ops::{delete::Delete, save::Save},
         Schema,
@@ -17,12 +20,27 @@ use plotline::{
 };
 use anyhow::Result;
 use clap::{Args, Subcommand};
+use serde::{Serialize, Serializer};
+
+use crate::{
+    error::CliError,
+    link::Link,
+    metadata::{self, Metadata},
+    output::{ErrorRecord, Output, Record},
+    repository::Locate,
+    tag::Tag,
+};
 
 /// A file-system document.
 #[derive(Debug, Clone)]
 pub struct Document {
+    /// The path of the document relative to the repository, without extension.
     pub path: PathBuf,
+    /// The extension of the documents in the repository.
+    pub extension: String,
     pub bytes: Vec<u8>,
+    /// The front matter of the document, parsed on first access.
+    metadata: OnceLock<Metadata>,
 }
 
 impl Identify for Document {
@@ -33,9 +51,168 @@ impl Identify for Document {
     }
 }
 
+impl Document {
+    /// Returns a new document with the given content.
+    pub fn new(path: PathBuf, extension: String, bytes: Vec<u8>) -> Self {
+        Self {
+            path,
+            extension,
+            bytes,
+            metadata: Default::default(),
+        }
+    }
+
+    /// Returns the front matter of the document.
+    pub fn metadata(&self) -> &Metadata {
+        self.metadata
+            .get_or_init(|| Metadata::parse(&String::from_utf8_lossy(&self.bytes)))
+    }
+
+    /// Returns the content of the document after its front matter.
+    pub fn body(&self) -> &[u8] {
+        let Ok(text) = std::str::from_utf8(&self.bytes) else {
+            return &self.bytes;
+        };
+
+        match metadata::split(text) {
+            Some((_, _, body)) => body.as_bytes(),
+            None => &self.bytes,
+        }
+    }
+
+    /// Returns the front matter of the document, delimiters included.
+    pub fn front_matter(&self) -> &[u8] {
+        &self.bytes[..self.bytes.len() - self.body().len()]
+    }
+}
+
+/// The textual content of a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Content(pub String);
+
+impl AsRef<str> for Content {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<DocumentRepo> Property<LazyDocument<DocumentRepo>> for Content
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    fn all(source: &LazyDocument<DocumentRepo>) -> Vec<Self> {
+        source
+            .try_deref()
+            .map(|document| Content(String::from_utf8_lossy(&document.bytes).into_owned()))
+            .into_iter()
+            .collect()
+    }
+}
+
+/// A document, as listed by the CLI.
+///
+/// The document is loaded only if the record gets serialized.
+pub struct DocumentRecord<'a, DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub document: &'a LazyDocument<DocumentRepo>,
+}
+
+impl<DocumentRepo> Serialize for DocumentRecord<'_, DocumentRepo>
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+    DocumentRepo::Error: Display,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[derive(Serialize)]
+        struct Fields<'a> {
+            id: &'a PathBuf,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            metadata: Option<&'a Metadata>,
+            links: Vec<Link>,
+            tags: Vec<Tag>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            error: Option<ErrorRecord>,
+        }
+
+        let document = self.document.try_deref();
+        Fields {
+            id: self.document.id(),
+            metadata: document.map(Document::metadata),
+            links: Link::all(self.document),
+            tags: Tag::all(self.document),
+            error: self.document.error().map(|err| ErrorRecord {
+                kind: "load",
+                message: err.to_string(),
+            }),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<DocumentRepo> Record for DocumentRecord<'_, DocumentRepo>
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+    DocumentRepo::Error: Display,
+{
+    fn write_text(&self, writer: &mut dyn Write) -> io::Result<()> {
+        writeln!(writer, "{:?}", self.document.id())
+    }
+}
+
+/// A change made to a document.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangeRecord {
+    pub id: PathBuf,
+    /// The operation applied to the document: either save or delete.
+    pub operation: &'static str,
+}
+
+impl Record for ChangeRecord {
+    /// Changes are silent in human-readable output.
+    fn write_text(&self, _: &mut dyn Write) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The content of a document.
+#[derive(Debug, Clone, Serialize)]
+pub struct ContentRecord {
+    pub id: PathBuf,
+    pub content: String,
+}
+
+impl Record for ContentRecord {
+    /// The content is written as is.
+    fn write_text(&self, writer: &mut dyn Write) -> io::Result<()> {
+        writer.write_all(self.content.as_bytes())
+    }
+}
+
+#[derive(Args)]
+struct DocumentShowArgs {
+    /// Show only the front matter of the document.
+    #[arg(long, conflicts_with = "body")]
+    front_matter: bool,
+    /// Show only the content after the front matter.
+    #[arg(long)]
+    body: bool,
+}
+
+#[derive(Args)]
+struct DocumentEditArgs {
+    /// The command to edit the document's file with.
+    #[arg(long, env = "EDITOR", default_value = "vi")]
+    editor: String,
+}
+
 #[derive(Args)]
 struct DocumentSaveArgs {
-    /// The content of the node.
+    /// The content of the node, read from the standard input if not set.
     content: Option<String>,
 }
 
@@ -44,11 +221,15 @@ struct DocumentSaveArgs {
 enum DocumentSubCommand {
     /// Delete a document.
     Delete,
+    /// Edit a document in an external editor, saving it once the editor exits.
+    Edit(DocumentEditArgs),
     /// List all documents.
     #[command(alias("ls"))]
     List,
     /// Save a document.
     Save(DocumentSaveArgs),
+    /// Print the content of a document.
+    Show(DocumentShowArgs),
 }
 
 /// Manage documents in the graph.
@@ -68,48 +249,244 @@ where
 {
     pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
     pub document_repo: Arc<DocumentRepo>,
+    /// The extension of the documents in the repository.
+    pub extension: String,
+    pub output: Output,
 }
 
 impl<DocumentRepo> DocumentCli<DocumentRepo>
 where
-    DocumentRepo: 'static + DocumentRepository<Document = Document>,
-    DocumentRepo::Document: Debug + Clone,
-    <DocumentRepo::Document as Identify>::Id: Ord + Clone + FromStr + Debug,
-    <<DocumentRepo::Document as Identify>::Id as FromStr>::Err: 'static + Error + Sync + Send,
+    DocumentRepo: 'static + DocumentRepository<Document = Document> + Locate,
+    DocumentRepo::Error: Display,
 {
     pub fn execute(&self, command: DocumentCommand) -> Result<()> {
         let document_id = || {
             command
                 .id
-                .map(|id| <DocumentRepo::Document as Identify>::Id::from_str(&id))
-                .transpose()
-                .map_err(anyhow::Error::new)?
-                .ok_or(anyhow::Error::msg("node id must be set"))
+                .map(PathBuf::from)
+                .ok_or(CliError::InvalidArgument("document id must be set".into()))
         };
 
         match command.subcommand {
             DocumentSubCommand::Delete => {
-                Delete::new(document_id()?).execute(self.schema.transaction())?
+                let document_id = document_id()?;
+                Delete::new(document_id.clone())
+                    .execute(self.schema.transaction())
+                    .map_err(|err| match err {
+                        plotline::schema::Error::Noop => {
+                            CliError::NotFound(format!("{document_id:?}")).into()
+                        }
+                        err => anyhow::Error::new(err),
+                    })?;
+                self.output.write([ChangeRecord {
+                    id: document_id,
+                    operation: "delete",
+                }])?;
             }
+            DocumentSubCommand::Edit(args) => self.edit(document_id()?, &args.editor)?,
             DocumentSubCommand::List => {
-                let mut stdout = io::stdout().lock();
-                self.schema
-                    .read()
-                    .into_iter()
-                    .for_each(|node| writeln!(stdout, "{:?}", node.id()).unwrap());
+                let graph = self.schema.read();
+                self.output.write(
+                    graph
+                        .into_iter()
+                        .map(|document| DocumentRecord { document }),
+                )?;
             }
             DocumentSubCommand::Save(args) => {
                 let document_id = document_id()?;
-                let document = Document {
-                    path: document_id.clone(),
-                    bytes: args.content.map(|s| s.into_bytes()).unwrap_or_default(),
+                let bytes = match args.content {
+                    Some(content) => content.into_bytes(),
+                    None => {
+                        let mut bytes = Vec::new();
+                        io::stdin().lock().read_to_end(&mut bytes)?;
+                        bytes
+                    }
                 };
 
+                let document = Document::new(document_id.clone(), self.extension.clone(), bytes);
+
                 Save::new(LazyDocument::new(self.document_repo.clone(), document))
                     .execute(self.schema.transaction())?;
+                self.output.write([ChangeRecord {
+                    id: document_id,
+                    operation: "save",
+                }])?;
+            }
+            DocumentSubCommand::Show(args) => {
+                let document_id = document_id()?;
+                let graph = self.schema.read();
+                let Some(document) = graph.get(&document_id) else {
+                    return Err(CliError::NotFound(format!("{document_id:?}")).into());
+                };
+
+                let Some(loaded) = document.try_deref() else {
+                    let err = match document.error() {
+                        Some(err) => anyhow::Error::msg(err.to_string()),
+                        None => anyhow::Error::msg("document is not loaded"),
+                    };
+
+                    return Err(err.context(CliError::Load(format!("{document_id:?}"))));
+                };
+
+                let bytes = if args.front_matter {
+                    loaded.front_matter()
+                } else if args.body {
+                    loaded.body()
+                } else {
+                    &loaded.bytes
+                };
+
+                self.output.write([ContentRecord {
+                    id: document_id.clone(),
+                    content: String::from_utf8_lossy(bytes).into_owned(),
+                }])?;
             }
         };
 
         Ok(())
     }
+
+    /// Opens the file of the given document in the given editor and, once it exits, saves the
+    /// document if it changed.
+    ///
+    /// If the save is rejected, the file is restored to its previous content.
+    fn edit(&self, document_id: PathBuf, editor: &str) -> Result<()> {
+        let path = self.document_repo.locate(&document_id);
+        let previous = match fs::read(&path) {
+            Ok(bytes) => Some(bytes),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err.into()),
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut args = editor.split_whitespace();
+        let program = args
+            .next()
+            .ok_or(CliError::InvalidArgument("editor must be set".into()))?;
+
+        let status = Command::new(program).args(args).arg(&path).status()?;
+        if !status.success() {
+            return Err(CliError::Editor {
+                editor: editor.to_string(),
+                status,
+            }
+            .into());
+        }
+
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                tracing::info!(
+                    id = document_id.to_string_lossy().to_string(),
+                    "document was not created"
+                );
+                return Ok(());
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        if previous.as_ref() == Some(&bytes) {
+            tracing::info!(
+                id = document_id.to_string_lossy().to_string(),
+                "document is unchanged"
+            );
+            return Ok(());
+        }
+
+        let document = Document::new(document_id.clone(), self.extension.clone(), bytes);
+        if let Err(err) = Save::new(LazyDocument::new(self.document_repo.clone(), document))
+            .execute(self.schema.transaction())
+        {
+            match previous {
+                Some(bytes) => fs::write(&path, bytes)?,
+                None => fs::remove_file(&path)?,
+            }
+
+            return Err(err.into());
+        }
+
+        self.output.write([ChangeRecord {
+            id: document_id,
+            operation: "save",
+        }])?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf, sync::Arc};
+
+    use plotline::{deref::TryDeref, graph::Graph, schema::Schema};
+
+    use crate::{error, output::Output, repository::LocalDocumentRepository};
+
+    use super::{Document, DocumentCli};
+
+    fn content(cli: &DocumentCli<LocalDocumentRepository>, id: &str) -> String {
+        let graph = cli.schema.read();
+        let document = graph.node(PathBuf::from(id));
+        String::from_utf8(
+            document
+                .try_deref()
+                .unwrap()
+                .try_deref()
+                .unwrap()
+                .bytes
+                .clone(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn front_matter_should_be_split_from_body() {
+        let document = Document::new(
+            "alice".into(),
+            "md".into(),
+            b"---\ntitle: Alice\n---\nAlice, #hero".to_vec(),
+        );
+
+        assert_eq!(document.front_matter(), b"---\ntitle: Alice\n---\n");
+        assert_eq!(document.body(), b"Alice, #hero");
+
+        let document = Document::new("bob".into(), "md".into(), b"Bob".to_vec());
+        assert!(document.front_matter().is_empty());
+        assert_eq!(document.body(), b"Bob");
+    }
+
+    #[test]
+    fn edited_documents_should_be_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = dir.path().to_path_buf();
+        fs::write(context.join("alice.md"), "Alice").unwrap();
+
+        let document_repo = Arc::new(LocalDocumentRepository {
+            context: context.clone(),
+            extension: "md".into(),
+        });
+
+        let cli = DocumentCli {
+            schema: Arc::new(Schema::from(Graph::from_iter(document_repo.all()))),
+            extension: "md".into(),
+            document_repo,
+            output: Output::default(),
+        };
+
+        cli.edit("alice".into(), "sed -i s/Alice/Alicia/").unwrap();
+        assert_eq!(content(&cli, "alice"), "Alicia");
+        assert_eq!(
+            fs::read_to_string(context.join("alice.md")).unwrap(),
+            "Alicia"
+        );
+
+        let err = cli.edit("alice".into(), "false").unwrap_err();
+        assert_eq!(error::kind(&err), "editor");
+
+        cli.edit("bob".into(), "true").unwrap();
+        assert!(cli.schema.read().node(PathBuf::from("bob")).is_virtual());
+    }
 }
@@ -0,0 +1,82 @@
+use std::{io, process::ExitStatus};
+
+/// The errors of the CLI itself, as opposed to those of the schema or the file-system.
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    /// An argument has an invalid value.
+    #[error("{0}")]
+    InvalidArgument(String),
+    /// A document does not exist.
+    #[error("document {0} does not exist")]
+    NotFound(String),
+    /// A resource required by the command is not installed.
+    #[error("{0} must be installed")]
+    MissingResource(&'static str),
+    /// A document exists but could not be loaded.
+    #[error("loading document {0}")]
+    Load(String),
+    /// No endpoint serves the requested method and path.
+    #[error("no route for {0}")]
+    NoRoute(String),
+    /// The editor did not exit successfully.
+    #[error("editor {editor} exited with {status}")]
+    Editor { editor: String, status: ExitStatus },
+}
+
+impl CliError {
+    /// Returns the kind of the error, as a machine-readable name.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CliError::InvalidArgument(_) => "invalid_argument",
+            CliError::NotFound(_) => "not_found",
+            CliError::MissingResource(_) => "missing_resource",
+            CliError::Load(_) => "load",
+            CliError::NoRoute(_) => "no_route",
+            CliError::Editor { .. } => "editor",
+        }
+    }
+}
+
+/// Returns the kind of the given error, as a machine-readable name.
+pub fn kind(err: &anyhow::Error) -> &'static str {
+    if let Some(err) = err.downcast_ref::<CliError>() {
+        return err.kind();
+    }
+
+    if let Some(err) = err.downcast_ref::<plotline::schema::Error>() {
+        return match err {
+            plotline::schema::Error::Noop => "noop",
+            plotline::schema::Error::DuplicatedPlugin(_) => "duplicated_plugin",
+            plotline::schema::Error::MissingDependency { .. } => "missing_dependency",
+            plotline::schema::Error::Msg(_) => "schema",
+        };
+    }
+
+    if err.downcast_ref::<io::Error>().is_some() {
+        return "io";
+    }
+
+    if err.downcast_ref::<serde_json::Error>().is_some() {
+        return "serialization";
+    }
+
+    "other"
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::{kind, CliError};
+
+    #[test]
+    fn errors_should_have_kinds() {
+        let err = anyhow::Error::new(CliError::NotFound("\"alice\"".into()));
+        assert_eq!(kind(&err), "not_found");
+        assert_eq!(err.to_string(), "document \"alice\" does not exist");
+
+        assert_eq!(kind(&plotline::schema::Error::Noop.into()), "noop");
+        assert_eq!(kind(&io::Error::other("boom").into()), "io");
+        assert_eq!(kind(&anyhow::Error::msg("boom")), "other");
+    }
+}
@@ -0,0 +1,195 @@
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use plotline::{
+    document::{lazy::LazyDocument, DocumentRepository},
+    export::{Exporter, Format},
+    property::Property,
+    schema::Schema,
+};
+use anyhow::Result;
+use clap::{Args, Subcommand, ValueEnum};
+use serde::Serialize;
+
+use crate::{
+    document::Document,
+    error::CliError,
+    link::Link,
+    metadata::Title,
+    output::{Output, OutputFormat, Record},
+};
+
+/// A node of an exported graph.
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNodeRecord {
+    pub id: PathBuf,
+    pub label: String,
+    pub cluster: Option<String>,
+    /// Whether the document is linked but does not exist.
+    #[serde(rename = "virtual")]
+    pub is_virtual: bool,
+    /// The ids of the exported documents this one links to.
+    pub links: Vec<PathBuf>,
+}
+
+impl Record for GraphNodeRecord {
+    fn write_text(&self, writer: &mut dyn Write) -> io::Result<()> {
+        writeln!(writer, "{:?}", self.id)
+    }
+}
+
+/// The formats the graph can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Dot,
+    Mermaid,
+    Graphml,
+}
+
+impl From<ExportFormat> for Format {
+    fn from(format: ExportFormat) -> Self {
+        match format {
+            ExportFormat::Dot => Format::Dot,
+            ExportFormat::Mermaid => Format::Mermaid,
+            ExportFormat::Graphml => Format::GraphMl,
+        }
+    }
+}
+
+/// What exported documents are labeled with.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Label {
+    /// The id of the document.
+    #[default]
+    Id,
+    /// The title of the document, if any, or its id otherwise.
+    Title,
+}
+
+#[derive(Args)]
+struct GraphExportArgs {
+    /// The output format, unless JSON records are requested before the command.
+    #[arg(long, value_enum)]
+    format: ExportFormat,
+    /// Export only the documents reachable from the given one.
+    #[arg(long)]
+    root: Vec<String>,
+    /// The maximum amount of links between the roots and the exported documents.
+    #[arg(long, requires = "root")]
+    depth: Option<usize>,
+    /// What to label documents with.
+    #[arg(long, value_enum, default_value_t)]
+    label: Label,
+    /// Group the documents in the same directory.
+    #[arg(long, conflicts_with = "cluster")]
+    cluster_dirs: bool,
+    /// Group the documents whose id starts with the given prefix.
+    #[arg(long)]
+    cluster: Vec<String>,
+}
+
+#[derive(Subcommand)]
+enum GraphSubCommand {
+    /// Export the documents and the links between them.
+    Export(GraphExportArgs),
+}
+
+/// Inspect the graph of documents.
+#[derive(Args)]
+pub struct GraphCommand {
+    /// The action to perform.
+    #[command(subcommand)]
+    subcommand: GraphSubCommand,
+}
+
+pub struct GraphCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+    pub output: Output,
+}
+
+impl<DocumentRepo> GraphCli<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document>,
+{
+    pub fn execute(&self, command: GraphCommand) -> Result<()> {
+        match command.subcommand {
+            GraphSubCommand::Export(args) => self.export(args),
+        }
+    }
+
+    fn export(&self, args: GraphExportArgs) -> Result<()> {
+        let graph = self.schema.snapshot();
+
+        let name = |id: &PathBuf| id.to_string_lossy().to_string();
+        let mut exporter =
+            Exporter::<_, Link>::new(&graph).with_label(move |id, document| {
+                match (args.label, document) {
+                    (Label::Title, Some(document)) => Title::all(document)
+                        .into_iter()
+                        .next()
+                        .map(|title| title.0)
+                        .unwrap_or_else(|| name(id)),
+                    _ => name(id),
+                }
+            });
+
+        let prefixes: Vec<_> = args.cluster.iter().map(PathBuf::from).collect();
+        if args.cluster_dirs {
+            exporter = exporter.with_cluster(|id, _| {
+                id.parent()
+                    .filter(|parent| *parent != Path::new(""))
+                    .map(|parent| parent.to_string_lossy().to_string())
+            });
+        } else if !prefixes.is_empty() {
+            exporter = exporter.with_cluster(move |id, _| {
+                prefixes
+                    .iter()
+                    .find(|prefix| id.starts_with(prefix))
+                    .map(|prefix| prefix.to_string_lossy().to_string())
+            });
+        }
+
+        for root in args.root.into_iter().map(PathBuf::from) {
+            if graph.node(root.clone()).is_virtual() {
+                return Err(CliError::NotFound(format!("{root:?}")).into());
+            }
+
+            exporter = exporter.with_root(root);
+        }
+
+        if let Some(depth) = args.depth {
+            exporter = exporter.with_depth(depth);
+        }
+
+        // Structured output takes precedence over the export format.
+        if self.output.format != OutputFormat::Text {
+            let subgraph = exporter.subgraph();
+            let mut links = vec![Vec::new(); subgraph.nodes.len()];
+            subgraph.edges.iter().for_each(|(source, target)| {
+                links[*source].push(subgraph.nodes[*target].id.clone())
+            });
+
+            return self.output.write(subgraph.nodes.into_iter().zip(links).map(
+                |(node, links)| GraphNodeRecord {
+                    id: node.id,
+                    label: node.label,
+                    cluster: node.cluster,
+                    is_virtual: node.is_virtual,
+                    links,
+                },
+            ));
+        }
+
+        let mut stdout = io::stdout().lock();
+        exporter.export(args.format.into(), &mut stdout)?;
+        stdout.flush()?;
+
+        Ok(())
+    }
+}
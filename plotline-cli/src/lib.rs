@@ -1,10 +1,44 @@
+use check::CheckCommand;
 use clap::Subcommand;
 use document::DocumentCommand;
+use graph::GraphCommand;
+use link::LinksCommand;
+use lsp::LspCommand;
+use search::SearchCommand;
+use serve::ServeCommand;
+use tag::TagsCommand;
+use tui::TuiCommand;
+use watch::WatchCommand;
 
+pub mod cache;
+pub mod check;
 pub mod document;
+pub mod error;
+pub mod graph;
+pub mod link;
+pub mod lsp;
+pub mod metadata;
+pub mod output;
 pub mod repository;
+pub mod search;
+pub mod serve;
+pub mod tag;
+pub mod tui;
+pub mod watch;
 
 #[derive(Subcommand)]
 pub enum CliCommand {
+    Check(CheckCommand),
     Doc(DocumentCommand),
+    Graph(GraphCommand),
+    /// List the documents a document links to.
+    Links(LinksCommand),
+    /// List the documents linking to a document, and where they do so.
+    Backlinks(LinksCommand),
+    Lsp(LspCommand),
+    Search(SearchCommand),
+    Serve(ServeCommand),
+    Tags(TagsCommand),
+    Tui(TuiCommand),
+    Watch(WatchCommand),
 }
@@ -0,0 +1,876 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    io::{self, Write},
+    ops::Range,
+    path::{Component, Path, PathBuf},
+    sync::{Arc, LazyLock},
+};
+
+use plotline::{
+    deref::{TryDeref, With},
+    document::{lazy::LazyDocument, DocumentRepository},
+    graph::Graph,
+    id::Identify,
+    property::Property,
+    schema::{ops::rename::Rename, resource::Res, Schema},
+};
+use plotline_plugin_index::{Index, IndexPlugin};
+use anyhow::Result;
+use clap::Args;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    document::Document,
+    error::CliError,
+    output::{Output, Record},
+};
+
+/// Matches wikilinks, inline links, full and collapsed reference links, and shortcut reference
+/// links, in that order of precedence.
+static LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"\[\[(?P<wiki>[^\[\]]+)\]\]",
+        r#"|!?\[(?P<text>[^\[\]]*)\]\(\s*<?(?P<destination>[^()\s<>]*)>?(?:\s+"[^"]*")?\s*\)"#,
+        r"|!?\[(?P<reference>[^\[\]]+)\]\[(?P<label>[^\[\]]*)\]",
+        r"|!?\[(?P<shortcut>[^\[\]]+)\]",
+    ))
+    .expect("pattern should be a valid regular expression")
+});
+
+/// Matches the definition of a reference link.
+static DEFINITION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^ {0,3}\[(?P<label>[^\[\]]+)\]:\s*<?(?P<destination>[^\s<>]+)>?(?:\s+.*)?$"#)
+        .expect("pattern should be a valid regular expression")
+});
+
+/// The amount of characters in the context of a link.
+const CONTEXT_WIDTH: usize = 80;
+
+/// The syntax a link is written with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    /// An inline link, like `[text](path.md)`.
+    Inline,
+    /// A reference link, like `[text][label]`, whose destination is defined elsewhere.
+    Reference,
+    /// A wikilink, like `[[path#anchor|alias]]`.
+    Wiki,
+}
+
+/// A link from a document to another one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Link {
+    /// The id of the linked document.
+    pub id: PathBuf,
+    /// The heading the link points to, if any.
+    pub anchor: Option<String>,
+    /// The text the link is displayed with, if any.
+    pub alias: Option<String>,
+    /// The syntax of the link.
+    pub kind: LinkKind,
+}
+
+impl Identify for Link {
+    type Id = PathBuf;
+
+    fn id(&self) -> &Self::Id {
+        &self.id
+    }
+}
+
+impl Property<Document> for Link {
+    fn all(source: &Document) -> Vec<Self> {
+        Link::occurrences(source)
+            .into_iter()
+            .map(|occurrence| occurrence.link)
+            .collect()
+    }
+}
+
+impl<DocumentRepo> Property<LazyDocument<DocumentRepo>> for Link
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    fn all(source: &LazyDocument<DocumentRepo>) -> Vec<Self> {
+        source.try_deref().map(Link::all).unwrap_or_default()
+    }
+}
+
+/// The id of a document linked from another one.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Target(pub PathBuf);
+
+impl Property<Document> for Target {
+    fn all(source: &Document) -> Vec<Self> {
+        Link::all(source)
+            .into_iter()
+            .map(|link| Target(link.id))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+}
+
+impl<DocumentRepo> Property<LazyDocument<DocumentRepo>> for Target
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    fn all(source: &LazyDocument<DocumentRepo>) -> Vec<Self> {
+        source.try_deref().map(Target::all).unwrap_or_default()
+    }
+}
+
+/// An index of documents by the documents they link to.
+pub type LinkIndex<T> = Index<T, Target>;
+
+/// Implements the [`Plugin`](plotline::schema::plugin::Plugin) trait for a [`LinkIndex`].
+pub type LinkPlugin<T> = IndexPlugin<T, Target>;
+
+/// A link, together with where it is written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Occurrence {
+    pub link: Link,
+    /// The number of the line the link is written in, starting at 1.
+    pub line: usize,
+    /// The range of bytes the link spans in its line.
+    pub range: Range<usize>,
+    /// The piece of the line around the link.
+    pub context: String,
+}
+
+impl Link {
+    /// Returns all the links in the given document, in order of appearance.
+    pub fn occurrences(source: &Document) -> Vec<Occurrence> {
+        let text = String::from_utf8_lossy(&source.bytes);
+        let lines = || {
+            let mut fenced = false;
+            text.lines().enumerate().filter(move |(_, line)| {
+                let trimmed = line.trim_start();
+                if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                    fenced = !fenced;
+                    return false;
+                }
+
+                !fenced
+            })
+        };
+
+        let definitions: BTreeMap<_, _> = lines()
+            .filter_map(|(_, line)| DEFINITION.captures(line))
+            .map(|captures| {
+                (
+                    captures["label"].to_lowercase(),
+                    captures["destination"].to_string(),
+                )
+            })
+            .collect();
+
+        lines()
+            .filter(|(_, line)| !DEFINITION.is_match(line))
+            .flat_map(|(index, line)| {
+                LINK.captures_iter(line)
+                    .filter_map(|captures| {
+                        let link = Link::parse(source, &definitions, &captures)?;
+                        let range = captures.get(0)?.range();
+                        Some(Occurrence {
+                            link,
+                            line: index + 1,
+                            context: context(line, range.clone()),
+                            range,
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Returns the link represented by the given captures of the [`LINK`] pattern, if any.
+    fn parse(
+        source: &Document,
+        definitions: &BTreeMap<String, String>,
+        captures: &Captures,
+    ) -> Option<Self> {
+        if let Some(wiki) = captures.name("wiki") {
+            let (target, alias) = match wiki.as_str().split_once('|') {
+                Some((target, alias)) => (target, Some(alias.trim().to_string())),
+                None => (wiki.as_str(), None),
+            };
+
+            let (path, anchor) = split_anchor(target.trim());
+            return Some(Link {
+                id: resolve(source, path)?,
+                anchor,
+                alias,
+                kind: LinkKind::Wiki,
+            });
+        }
+
+        let (text, destination, kind) = if let Some(destination) = captures.name("destination") {
+            (&captures["text"], destination.as_str(), LinkKind::Inline)
+        } else if let Some(reference) = captures.name("reference") {
+            let label = match &captures["label"] {
+                "" => reference.as_str(),
+                label => label,
+            };
+
+            let destination = definitions.get(&label.to_lowercase())?;
+            (
+                reference.as_str(),
+                destination.as_str(),
+                LinkKind::Reference,
+            )
+        } else {
+            let shortcut = captures.name("shortcut")?.as_str();
+            let destination = definitions.get(&shortcut.to_lowercase())?;
+            (shortcut, destination.as_str(), LinkKind::Reference)
+        };
+
+        if destination.contains("://") || destination.starts_with("mailto:") {
+            return None;
+        }
+
+        let (path, anchor) = split_anchor(destination);
+        Some(Link {
+            id: resolve(source, &percent_decode(path))?,
+            anchor,
+            alias: (!text.is_empty()).then(|| text.to_string()),
+            kind,
+        })
+    }
+}
+
+/// Returns the piece of the given line around the given range, at most [`CONTEXT_WIDTH`]
+/// characters long unless the range itself is longer.
+fn context(line: &str, range: Range<usize>) -> String {
+    let before: Vec<char> = line[..range.start].chars().collect();
+    let after: Vec<char> = line[range.end..].chars().collect();
+    let budget = CONTEXT_WIDTH.saturating_sub(line[range.clone()].chars().count());
+
+    // Half of the budget goes to each side, and what one side does not use goes to the other.
+    let end = after.len().min(budget - before.len().min(budget / 2));
+    let start = before.len() - before.len().min(budget - end);
+
+    let mut context = String::new();
+    if start > 0 {
+        context.push('…');
+    }
+
+    context.extend(&before[start..]);
+    context.push_str(&line[range]);
+    context.extend(&after[..end]);
+    if end < after.len() {
+        context.push('…');
+    }
+
+    context.trim().to_string()
+}
+
+/// Splits the heading anchor, if any, from the given link target.
+fn split_anchor(target: &str) -> (&str, Option<String>) {
+    match target.split_once('#') {
+        Some((path, anchor)) => (path, Some(anchor.to_string())),
+        None => (target, None),
+    }
+}
+
+/// Decodes the percent-encoded bytes in the given path.
+pub(crate) fn percent_decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = path
+            .get(index + 1..index + 3)
+            .filter(|_| bytes[index] == b'%')
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match byte {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Returns the repository id the given path points to from the given document, if any.
+///
+/// Relative paths are resolved against the directory of the document, while absolute ones are
+/// resolved against the root of the repository. Paths with an extension other than the
+/// repository's one do not point to documents.
+fn resolve(source: &Document, path: &str) -> Option<PathBuf> {
+    if path.is_empty() {
+        return None;
+    }
+
+    let path = Path::new(path);
+    let base = match path.has_root() {
+        true => Path::new(""),
+        false => source.path.parent().unwrap_or(Path::new("")),
+    };
+
+    let mut id = PathBuf::new();
+    for component in base.components().chain(path.components()) {
+        match component {
+            Component::Normal(name) => id.push(name),
+            Component::ParentDir => {
+                if !id.pop() {
+                    return None;
+                }
+            }
+            Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+        }
+    }
+
+    match id.extension() {
+        Some(extension) if extension == source.extension.as_str() => {
+            id.set_extension("");
+            Some(id)
+        }
+        Some(_) => None,
+        None => Some(id),
+    }
+}
+
+/// Returns the text of the given document with its links rewritten as if it was moved to the
+/// given id, and with every link to `from` pointing to `to` instead.
+///
+/// Links keep their syntax, anchor and alias, as well as whether they are relative and written
+/// with an extension.
+pub fn relink(source: &Document, id: &Path, from: &Path, to: &Path) -> String {
+    let moved = source.path != id;
+    let target = |path: &str| {
+        let linked = resolve(source, path)?;
+        let absolute = path.starts_with('/');
+        let linked = match linked == from {
+            true => to.to_path_buf(),
+            false if moved && !absolute => linked,
+            false => return None,
+        };
+
+        let mut target = match absolute {
+            true => Path::new("/").join(linked),
+            false => relative(id, &linked),
+        };
+
+        if Path::new(path).extension().is_some() {
+            target
+                .as_mut_os_string()
+                .push(format!(".{}", source.extension));
+        }
+
+        Some(target.to_string_lossy().into_owned())
+    };
+
+    // Destinations cannot contain whitespaces, while wikilinks can.
+    let destination = |path: &str| {
+        if path.contains("://") || path.starts_with("mailto:") {
+            return None;
+        }
+
+        target(&percent_decode(path)).map(|target| target.replace(' ', "%20"))
+    };
+
+    let text = String::from_utf8_lossy(&source.bytes);
+    let mut relinked = String::with_capacity(text.len());
+    let mut fenced = false;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let fence = trimmed.starts_with("```") || trimmed.starts_with("~~~");
+        fenced ^= fence;
+        if fenced || fence {
+            relinked.push_str(line);
+            continue;
+        }
+
+        let content = line.trim_end_matches(['\r', '\n']);
+        let mut replacements = Vec::new();
+        if let Some(captures) = DEFINITION.captures(content) {
+            let m = captures
+                .name("destination")
+                .expect("destination should be captured");
+            let path = m.as_str().split('#').next().unwrap_or_default();
+            replacements.extend(destination(path).map(|target| (m.start(), path, target)));
+        } else {
+            for captures in LINK.captures_iter(content) {
+                if let Some(m) = captures.name("wiki") {
+                    let written = m.as_str().split('|').next().unwrap_or_default();
+                    let path = written.trim().split('#').next().unwrap_or_default();
+                    let start = m.start() + written.len() - written.trim_start().len();
+                    replacements.extend(target(path).map(|target| (start, path, target)));
+                } else if let Some(m) = captures.name("destination") {
+                    let path = m.as_str().split('#').next().unwrap_or_default();
+                    replacements.extend(destination(path).map(|target| (m.start(), path, target)));
+                }
+            }
+        }
+
+        let mut line = line.to_string();
+        for (start, path, target) in replacements.into_iter().rev() {
+            line.replace_range(start..start + path.len(), &target);
+        }
+
+        relinked.push_str(&line);
+    }
+
+    relinked
+}
+
+/// Returns the path leading from the directory of the given document to the given id.
+pub(crate) fn relative(source: &Path, id: &Path) -> PathBuf {
+    let base: Vec<_> = source
+        .parent()
+        .unwrap_or(Path::new(""))
+        .components()
+        .collect();
+    let components: Vec<_> = id.components().collect();
+    let common = base
+        .iter()
+        .zip(&components)
+        .take_while(|(base, component)| base == component)
+        .count();
+
+    base[common..]
+        .iter()
+        .map(|_| Component::ParentDir)
+        .chain(components[common..].iter().copied())
+        .collect()
+}
+
+/// Returns the rename of the given document to the given id, together with the documents linking
+/// to it with their links rewritten.
+pub fn rename<DocumentRepo>(
+    graph: &Graph<LazyDocument<DocumentRepo>>,
+    links: &LinkIndex<LazyDocument<DocumentRepo>>,
+    document_repo: &Arc<DocumentRepo>,
+    from: PathBuf,
+    to: PathBuf,
+) -> Result<Rename<LazyDocument<DocumentRepo>>>
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    let node = graph.node(from.clone());
+    let Some(document) = node.try_deref().and_then(|document| document.try_deref()) else {
+        return Err(CliError::NotFound(format!("{from:?}")).into());
+    };
+
+    let lazy = |id: PathBuf, text: String| {
+        let document = Document::new(id, document.extension.clone(), text.into_bytes());
+        LazyDocument::new(document_repo.clone(), document)
+    };
+
+    let text = relink(document, &to, &from, &to);
+    let mut rename = Rename::new(from.clone(), lazy(to.clone(), text));
+
+    let sources: BTreeSet<_> = incoming(graph, links, from.clone(), 1)
+        .into_iter()
+        .map(|record| record.source)
+        .filter(|source| source != &from)
+        .collect();
+
+    for source in sources {
+        let node = graph.node(source.clone());
+        let Some(document) = node.try_deref().and_then(|document| document.try_deref()) else {
+            continue;
+        };
+
+        rename = rename.with_reference(lazy(source, relink(document, &document.path, &from, &to)));
+    }
+
+    Ok(rename)
+}
+
+/// List the documents linked from or to a document.
+#[derive(Args)]
+pub struct LinksCommand {
+    /// The id of the document.
+    id: String,
+    /// The maximum amount of links between the document and the listed ones.
+    #[arg(long, default_value_t = 1)]
+    depth: usize,
+}
+
+/// A link from a document, as listed by the CLI.
+#[derive(Debug, Clone, Serialize)]
+pub struct LinkRecord {
+    /// The id of the linking document.
+    pub source: PathBuf,
+    #[serde(flatten)]
+    pub link: Link,
+    /// Whether the linked document does not exist.
+    #[serde(rename = "virtual")]
+    pub is_virtual: bool,
+    /// The amount of links between the listed document and this one.
+    pub depth: usize,
+}
+
+impl Record for LinkRecord {
+    fn write_text(&self, writer: &mut dyn Write) -> io::Result<()> {
+        write!(writer, "{:?} -> {:?}", self.source, self.link.id)?;
+        if self.is_virtual {
+            write!(writer, " (virtual)")?;
+        }
+
+        writeln!(writer)
+    }
+}
+
+/// A link to a document, as listed by the CLI.
+#[derive(Debug, Clone, Serialize)]
+pub struct BacklinkRecord {
+    /// The id of the linking document.
+    pub source: PathBuf,
+    #[serde(flatten)]
+    pub link: Link,
+    /// The number of the line the link is written in, starting at 1.
+    pub line: usize,
+    /// The range of bytes the link spans in its line.
+    #[serde(skip)]
+    pub range: Range<usize>,
+    /// The piece of the line around the link.
+    pub context: String,
+    /// The amount of links between this document and the listed one.
+    pub depth: usize,
+}
+
+impl Record for BacklinkRecord {
+    fn write_text(&self, writer: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            writer,
+            "{:?}:{} -> {:?}",
+            self.source, self.line, self.link.id
+        )?;
+        writeln!(writer, "    {}", self.context)
+    }
+}
+
+pub struct LinksCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+    pub output: Output,
+}
+
+impl<DocumentRepo> LinksCli<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document>,
+{
+    /// Lists the links from the given document, following them up to the given depth.
+    pub fn links(&self, command: LinksCommand) -> Result<()> {
+        let root = PathBuf::from(command.id);
+        if self.schema.read().node(root.clone()).is_virtual() {
+            return Err(CliError::NotFound(format!("{root:?}")).into());
+        }
+
+        self.output
+            .write(outgoing(&self.schema.read(), root, command.depth))?;
+        Ok(())
+    }
+
+    /// Lists the links to the given document, following them backwards up to the given depth.
+    ///
+    /// The document may not exist, so the documents linking to a missing one can be found.
+    pub fn backlinks(&self, command: LinksCommand) -> Result<()> {
+        let (root, graph) = (PathBuf::from(command.id), self.schema.read());
+        let records = Res::<LinkIndex<LazyDocument<DocumentRepo>>>::from(self.schema.resources())
+            .with(|links| incoming(&graph, links, root, command.depth))
+            .ok_or(CliError::MissingResource("link index"))?;
+
+        self.output.write(records)?;
+        Ok(())
+    }
+}
+
+/// Returns the links from the given document, following them up to the given depth.
+pub fn outgoing<DocumentRepo>(
+    graph: &Graph<LazyDocument<DocumentRepo>>,
+    root: PathBuf,
+    depth: usize,
+) -> Vec<LinkRecord>
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    let mut records = Vec::new();
+    let mut visited = BTreeSet::from([root.clone()]);
+    let mut queue = VecDeque::from([(root, 0)]);
+    while let Some((source, distance)) = queue.pop_front() {
+        if distance >= depth {
+            continue;
+        }
+
+        let node = graph.node(source.clone());
+        for link in node.try_deref().map(Link::all).unwrap_or_default() {
+            let is_virtual = graph.node(link.id.clone()).is_virtual();
+            if !is_virtual && visited.insert(link.id.clone()) {
+                queue.push_back((link.id.clone(), distance + 1));
+            }
+
+            records.push(LinkRecord {
+                source: source.clone(),
+                link,
+                is_virtual,
+                depth: distance + 1,
+            });
+        }
+    }
+
+    records
+}
+
+/// Returns the links to the given document, following them backwards up to the given depth.
+///
+/// Only the documents the given index holds as linking to each target are read.
+pub fn incoming<DocumentRepo>(
+    graph: &Graph<LazyDocument<DocumentRepo>>,
+    links: &LinkIndex<LazyDocument<DocumentRepo>>,
+    root: PathBuf,
+    depth: usize,
+) -> Vec<BacklinkRecord>
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    let mut records = Vec::new();
+    let mut visited = BTreeSet::from([root.clone()]);
+    let mut queue = VecDeque::from([(root, 0)]);
+    while let Some((target, distance)) = queue.pop_front() {
+        if distance >= depth {
+            continue;
+        }
+
+        for source in links.get(&Target(target.clone())) {
+            let node = graph.node(source.clone());
+            let Some(document) = node.try_deref().and_then(|document| document.try_deref()) else {
+                continue;
+            };
+
+            if visited.insert(source.clone()) {
+                queue.push_back((source.clone(), distance + 1));
+            }
+
+            Link::occurrences(document)
+                .into_iter()
+                .filter(|occurrence| occurrence.link.id == target)
+                .for_each(|occurrence| {
+                    records.push(BacklinkRecord {
+                        source: source.clone(),
+                        link: occurrence.link,
+                        line: occurrence.line,
+                        range: occurrence.range,
+                        context: occurrence.context,
+                        depth: distance + 1,
+                    })
+                });
+        }
+    }
+
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        path::{Path, PathBuf},
+        sync::Arc,
+    };
+
+    use plotline::{graph::Graph, id::Identify, property::Property};
+
+    use crate::{document::Document, repository::LocalDocumentRepository};
+
+    use super::{
+        context, incoming, outgoing, relink, Link, LinkIndex, LinkKind, Target, CONTEXT_WIDTH,
+    };
+
+    fn links(path: &str, text: &str) -> Vec<Link> {
+        Link::all(&Document::new(
+            PathBuf::from(path),
+            "md".into(),
+            text.as_bytes().to_vec(),
+        ))
+    }
+
+    fn link(id: &str, anchor: Option<&str>, alias: Option<&str>, kind: LinkKind) -> Link {
+        Link {
+            id: PathBuf::from(id),
+            anchor: anchor.map(ToString::to_string),
+            alias: alias.map(ToString::to_string),
+            kind,
+        }
+    }
+
+    #[test]
+    fn inline_links_should_be_resolved() {
+        assert_eq!(
+            links(
+                "characters/alice",
+                "Friend of [Bob](bob.md#family) in [the city](../places/My%20City.md \"City\").\n\
+                 See [home](https://example.com) and ![map](map.png).",
+            ),
+            vec![
+                link(
+                    "characters/bob",
+                    Some("family"),
+                    Some("Bob"),
+                    LinkKind::Inline
+                ),
+                link("places/My City", None, Some("the city"), LinkKind::Inline),
+            ]
+        );
+    }
+
+    #[test]
+    fn reference_links_should_be_resolved() {
+        assert_eq!(
+            links(
+                "characters/alice",
+                "Lives in [the city][city], near [Forest][] and [unknown][nope].\n\n\
+                 [city]: /places/city.md\n\
+                 [forest]: <../places/forest> \"The forest\"",
+            ),
+            vec![
+                link("places/city", None, Some("the city"), LinkKind::Reference),
+                link("places/forest", None, Some("Forest"), LinkKind::Reference),
+            ]
+        );
+    }
+
+    #[test]
+    fn wikilinks_should_be_resolved() {
+        assert_eq!(
+            links(
+                "characters/alice",
+                "Sister of [[bob]], born in [[../places/city#History|her city]].\n\
+                 ```\n[[ignored]]\n```",
+            ),
+            vec![
+                link("characters/bob", None, None, LinkKind::Wiki),
+                link(
+                    "places/city",
+                    Some("History"),
+                    Some("her city"),
+                    LinkKind::Wiki
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn links_outside_the_repository_should_be_ignored() {
+        assert!(links("alice", "[[../../outside]] [x](../y.md)").is_empty());
+    }
+
+    #[test]
+    fn occurrences_should_be_located() {
+        let document = Document::new(
+            PathBuf::from("alice"),
+            "md".into(),
+            b"# Alice\n\n```\n[[ignored]]\n```\nSister of [[bob]].".to_vec(),
+        );
+
+        let occurrences = Link::occurrences(&document);
+        assert_eq!(occurrences.len(), 1);
+        assert_eq!(occurrences[0].line, 6);
+        assert_eq!(occurrences[0].context, "Sister of [[bob]].");
+    }
+
+    #[test]
+    fn long_contexts_should_be_shortened() {
+        let line = format!("{}[[bob]]{}", "a".repeat(100), "b".repeat(10));
+        let context = context(&line, 100..107);
+        assert_eq!(context.chars().count(), CONTEXT_WIDTH + 1);
+        assert!(context.starts_with('…'));
+        assert!(context.ends_with(&format!("[[bob]]{}", "b".repeat(10))));
+    }
+
+    #[test]
+    fn links_should_be_followed_up_to_depth() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = dir.path().to_path_buf();
+        std::fs::write(context.join("alice.md"), "[[bob]] and [[nobody]]").unwrap();
+        std::fs::write(context.join("bob.md"), "Friend of\n[[carol]]").unwrap();
+        std::fs::write(context.join("carol.md"), "Carol").unwrap();
+
+        let document_repo = Arc::new(LocalDocumentRepository {
+            context,
+            extension: "md".into(),
+        });
+
+        let graph = Graph::from_iter(document_repo.all());
+        let links: Vec<_> = outgoing(&graph, "alice".into(), 1)
+            .into_iter()
+            .map(|record| (record.link.id, record.is_virtual, record.depth))
+            .collect();
+        assert_eq!(
+            links,
+            vec![("bob".into(), false, 1), ("nobody".into(), true, 1)]
+        );
+
+        assert_eq!(outgoing(&graph, "alice".into(), 2).len(), 3);
+
+        let mut index = LinkIndex::default();
+        graph.into_iter().for_each(|document| {
+            index.insert(document.id().clone(), Target::all(document));
+        });
+
+        let backlinks: Vec<_> = incoming(&graph, &index, "carol".into(), 2)
+            .into_iter()
+            .map(|record| (record.source, record.line, record.depth))
+            .collect();
+        assert_eq!(
+            backlinks,
+            vec![("bob".into(), 2, 1), ("alice".into(), 1, 2)]
+        );
+
+        let backlinks = incoming(&graph, &index, "nobody".into(), 1);
+        assert_eq!(backlinks.len(), 1);
+        assert_eq!(backlinks[0].context, "[[bob]] and [[nobody]]");
+    }
+
+    #[test]
+    fn links_should_be_rewritten_on_rename() {
+        let document = Document::new(
+            PathBuf::from("characters/alice"),
+            "md".into(),
+            "See [[bob#family|Bob]], [the city](../places/city.md) and [Bob][bob].\n\
+             ```\n[[bob]]\n```\n\
+             [bob]: bob.md \"Bob\"\n"
+                .as_bytes()
+                .to_vec(),
+        );
+
+        assert_eq!(
+            relink(
+                &document,
+                Path::new("characters/alice"),
+                Path::new("characters/bob"),
+                Path::new("people/Robert Smith"),
+            ),
+            "See [[../people/Robert Smith#family|Bob]], [the city](../places/city.md) and [Bob][bob].\n\
+             ```\n[[bob]]\n```\n\
+             [bob]: ../people/Robert%20Smith.md \"Bob\"\n"
+        );
+
+        assert_eq!(
+            relink(
+                &document,
+                Path::new("alice"),
+                Path::new("nobody"),
+                Path::new("somebody"),
+            ),
+            "See [[characters/bob#family|Bob]], [the city](places/city.md) and [Bob][bob].\n\
+             ```\n[[bob]]\n```\n\
+             [bob]: characters/bob.md \"Bob\"\n",
+            "relative links should be kept when moving the document"
+        );
+    }
+}
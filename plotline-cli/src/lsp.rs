@@ -0,0 +1,792 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Result;
+use clap::Args;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+        PublishDiagnostics,
+    },
+    request::{Completion, GotoDefinition, References, Rename, Request as _},
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, CompletionResponse,
+    CompletionTextEdit, Diagnostic, DiagnosticSeverity, DidChangeTextDocumentParams,
+    DidCloseTextDocumentParams, DidOpenTextDocumentParams, DocumentChangeOperation,
+    DocumentChanges, GotoDefinitionParams, GotoDefinitionResponse, Location, NumberOrString, OneOf,
+    OptionalVersionedTextDocumentIdentifier, Position, PublishDiagnosticsParams, Range,
+    ReferenceParams, RenameFile, RenameParams, ResourceOp, ServerCapabilities, TextDocumentEdit,
+    TextDocumentPositionParams, TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit, Url,
+    WorkspaceEdit,
+};
+use plotline::{
+    deref::{TryDeref, With},
+    document::lazy::LazyDocument,
+    graph::Graph,
+    property::Property,
+    schema::{
+        ops::{delete::Delete, save::Save},
+        resource::Res,
+        Error, Schema,
+    },
+};
+
+use crate::{
+    document::Document,
+    error::{self, CliError},
+    link::{self, Link, LinkIndex, Occurrence},
+    metadata::Title,
+    repository::LocalDocumentRepository,
+};
+
+/// The source diagnostics are reported from.
+const SOURCE: &str = "plotline";
+
+/// Speak the Language Server Protocol over the standard input and output.
+#[derive(Args)]
+pub struct LspCommand;
+
+pub struct LspCli {
+    pub schema: Arc<Schema<LazyDocument<LocalDocumentRepository>>>,
+    pub document_repo: Arc<LocalDocumentRepository>,
+}
+
+impl LspCli {
+    pub fn execute(&self, _: LspCommand) -> Result<()> {
+        let (connection, io_threads) = Connection::stdio();
+        let capabilities = ServerCapabilities {
+            text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+            completion_provider: Some(CompletionOptions {
+                trigger_characters: Some(vec!["[".into(), "(".into()]),
+                ..Default::default()
+            }),
+            definition_provider: Some(OneOf::Left(true)),
+            references_provider: Some(OneOf::Left(true)),
+            rename_provider: Some(OneOf::Left(true)),
+            ..Default::default()
+        };
+
+        connection.initialize(serde_json::to_value(capabilities)?)?;
+        self.serve(&connection)?;
+
+        drop(connection);
+        io_threads.join()?;
+        Ok(())
+    }
+
+    /// Handles the messages of the given initialized connection until the client shuts it down.
+    pub fn serve(&self, connection: &Connection) -> Result<()> {
+        let mut session = Session {
+            cli: self,
+            context: fs::canonicalize(&self.document_repo.context)?,
+            open: BTreeSet::new(),
+            connection,
+        };
+
+        for message in &connection.receiver {
+            let changed = match message {
+                Message::Request(request) => {
+                    if connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+
+                    let changed = request.method == Rename::METHOD;
+                    connection.sender.send(session.request(request).into())?;
+                    changed
+                }
+                Message::Notification(notification) => {
+                    let method = notification.method.clone();
+                    if let Err(err) = session.notify(notification) {
+                        tracing::error!(
+                            error = format!("{err:#}"),
+                            method,
+                            "handling notification"
+                        );
+                    }
+
+                    true
+                }
+                Message::Response(_) => false,
+            };
+
+            // Any change may create or fix dangling links in every open document.
+            if changed {
+                session.publish()?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The state of a connection with a client.
+struct Session<'a> {
+    cli: &'a LspCli,
+    /// The canonical path of the repository, which document URIs are relative to.
+    context: PathBuf,
+    /// The ids of the documents open in the client.
+    open: BTreeSet<PathBuf>,
+    connection: &'a Connection,
+}
+
+impl Session<'_> {
+    fn request(&mut self, request: Request) -> Response {
+        match request.method.as_str() {
+            Completion::METHOD => self.handle::<Completion>(request, Self::completion),
+            GotoDefinition::METHOD => self.handle::<GotoDefinition>(request, Self::definition),
+            References::METHOD => self.handle::<References>(request, Self::references),
+            Rename::METHOD => self.handle::<Rename>(request, Self::rename),
+            _ => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                CliError::NoRoute(request.method).to_string(),
+            ),
+        }
+    }
+
+    /// Answers the given request of type R with the given handler.
+    fn handle<R>(
+        &mut self,
+        request: Request,
+        handler: impl FnOnce(&mut Self, R::Params) -> Result<R::Result>,
+    ) -> Response
+    where
+        R: lsp_types::request::Request,
+    {
+        let result = serde_json::from_value(request.params)
+            .map_err(|err| CliError::InvalidArgument(err.to_string()).into())
+            .and_then(|params| handler(self, params));
+
+        match result {
+            Ok(result) => Response::new_ok(request.id, result),
+            Err(err) => {
+                let code = match error::kind(&err) {
+                    "invalid_argument" => ErrorCode::InvalidParams,
+                    _ => ErrorCode::RequestFailed,
+                };
+
+                Response::new_err(request.id, code as i32, format!("{err:#}"))
+            }
+        }
+    }
+
+    fn notify(&mut self, notification: Notification) -> Result<()> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let document_id = self.id(&params.text_document.uri)?;
+                self.save(document_id.clone(), params.text_document.text)?;
+                self.open.insert(document_id);
+            }
+            DidChangeTextDocument::METHOD => {
+                let mut params: DidChangeTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+
+                // Documents are synced in full, so the last change holds the whole text.
+                let Some(change) = params.content_changes.pop() else {
+                    return Ok(());
+                };
+
+                self.save(self.id(&params.text_document.uri)?, change.text)?;
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let document_id = self.id(&params.text_document.uri)?;
+                self.open.remove(&document_id);
+                self.diagnose(params.text_document.uri, Vec::new())?;
+
+                // Unsaved changes are discarded, so the document is read again from its file.
+                let document_repo = &self.cli.document_repo;
+                if document_repo.path(&document_id).exists() {
+                    Save::new(LazyDocument::builder(document_repo.clone())(document_id))
+                        .execute(self.cli.schema.transaction())?;
+                } else if let Err(err) =
+                    Delete::new(document_id).execute(self.cli.schema.transaction())
+                {
+                    if !matches!(err, Error::Noop) {
+                        return Err(err.into());
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Saves the given text as the content of the document with the given id.
+    fn save(&self, document_id: PathBuf, text: String) -> Result<()> {
+        let document = Document::new(
+            document_id,
+            self.cli.document_repo.extension.clone(),
+            text.into_bytes(),
+        );
+
+        Save::new(LazyDocument::new(self.cli.document_repo.clone(), document))
+            .execute(self.cli.schema.transaction())?;
+        Ok(())
+    }
+
+    /// Publishes the diagnostics of every open document.
+    fn publish(&self) -> Result<()> {
+        let diagnostics: Vec<_> = {
+            let graph = self.cli.schema.read();
+            self.open
+                .iter()
+                .map(|document_id| (document_id, diagnostics(&graph, document_id)))
+                .collect()
+        };
+
+        for (document_id, diagnostics) in diagnostics {
+            self.diagnose(self.uri(document_id)?, diagnostics)?;
+        }
+
+        Ok(())
+    }
+
+    fn diagnose(&self, uri: Url, diagnostics: Vec<Diagnostic>) -> Result<()> {
+        let params = PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version: None,
+        };
+
+        self.connection
+            .sender
+            .send(Notification::new(PublishDiagnostics::METHOD.into(), params).into())?;
+        Ok(())
+    }
+
+    /// Completes the id of the document a link being written points to.
+    fn completion(&mut self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
+        let TextDocumentPositionParams {
+            text_document,
+            position,
+        } = params.text_document_position;
+
+        let document_id = self.id(&text_document.uri)?;
+        let graph = self.cli.schema.read();
+        let text = text(&graph, &document_id)?;
+        let Some((line, cursor)) = offset(&text, position) else {
+            return Ok(None);
+        };
+
+        // The link being written is the last one opened, and not closed, before the cursor.
+        let before = &line[..cursor];
+        let wiki = before
+            .rfind("[[")
+            .filter(|&start| !before[start..].contains("]]"));
+        let inline = before
+            .rfind("](")
+            .filter(|&start| !before[start..].contains(')'));
+        let (start, is_wiki) = match (wiki, inline) {
+            (Some(wiki), Some(inline)) if inline > wiki => (inline + 2, false),
+            (Some(wiki), _) => (wiki + 2, true),
+            (None, Some(inline)) => (inline + 2, false),
+            (None, None) => return Ok(None),
+        };
+
+        if before[start..].contains(['|', '#']) {
+            return Ok(None);
+        }
+
+        let range = Range::new(
+            Position::new(position.line, utf16_len(&line[..start])),
+            position,
+        );
+
+        let extension = &self.cli.document_repo.extension;
+        let items = graph
+            .into_iter()
+            .filter_map(|document| document.try_deref())
+            .map(|document| {
+                let target = link::relative(&document_id, &document.path);
+                let target = match is_wiki {
+                    true => target.to_string_lossy().into_owned(),
+                    false => {
+                        format!("{}.{extension}", target.to_string_lossy()).replace(' ', "%20")
+                    }
+                };
+
+                CompletionItem {
+                    label: target.clone(),
+                    kind: Some(CompletionItemKind::FILE),
+                    detail: Title::all(document)
+                        .into_iter()
+                        .next()
+                        .map(|title| title.0)
+                        .or_else(|| Some(document.path.to_string_lossy().into_owned())),
+                    text_edit: Some(CompletionTextEdit::Edit(TextEdit::new(range, target))),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        Ok(Some(CompletionResponse::Array(items)))
+    }
+
+    /// Returns the location of the document linked at the given position, if it exists.
+    fn definition(
+        &mut self,
+        params: GotoDefinitionParams,
+    ) -> Result<Option<GotoDefinitionResponse>> {
+        let TextDocumentPositionParams {
+            text_document,
+            position,
+        } = params.text_document_position_params;
+
+        let document_id = self.id(&text_document.uri)?;
+        let graph = self.cli.schema.read();
+        let Some(occurrence) = occurrence(&graph, &document_id, position)? else {
+            return Ok(None);
+        };
+
+        if graph.node(occurrence.link.id.clone()).is_virtual() {
+            return Ok(None);
+        }
+
+        Ok(Some(GotoDefinitionResponse::Scalar(Location::new(
+            self.uri(&occurrence.link.id)?,
+            Range::default(),
+        ))))
+    }
+
+    /// Returns the locations of every link to the document linked at the given position or, if
+    /// none, to the document itself.
+    fn references(&mut self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
+        let TextDocumentPositionParams {
+            text_document,
+            position,
+        } = params.text_document_position;
+
+        let document_id = self.id(&text_document.uri)?;
+        let graph = self.cli.schema.read();
+        let target = occurrence(&graph, &document_id, position)?
+            .map(|occurrence| occurrence.link.id)
+            .unwrap_or(document_id);
+
+        let locations = Res::<LinkIndex<LazyDocument<LocalDocumentRepository>>>::from(
+            self.cli.schema.resources(),
+        )
+        .with(|links| link::incoming(&graph, links, target, 1))
+        .ok_or(CliError::MissingResource("link index"))?
+        .into_iter()
+        .map(|backlink| {
+            let text = text(&graph, &backlink.source)?;
+            let line = backlink.line - 1;
+            Ok(Location::new(
+                self.uri(&backlink.source)?,
+                Range::new(
+                    position_of(&text, line, backlink.range.start),
+                    position_of(&text, line, backlink.range.end),
+                ),
+            ))
+        })
+        .collect::<Result<_>>()?;
+
+        Ok(Some(locations))
+    }
+
+    /// Renames the document linked at the given position or, if none, the document itself,
+    /// rewriting every link to it.
+    fn rename(&mut self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
+        let TextDocumentPositionParams {
+            text_document,
+            position,
+        } = params.text_document_position;
+
+        let extension = format!(".{}", self.cli.document_repo.extension);
+        let to = PathBuf::from(params.new_name.trim_end_matches(&extension));
+        if to.as_os_str().is_empty()
+            || !to
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(CliError::InvalidArgument(format!(
+                "{} is not a valid document id",
+                params.new_name
+            ))
+            .into());
+        }
+
+        let document_id = self.id(&text_document.uri)?;
+        let (rename, operations) = {
+            let graph = self.cli.schema.read();
+            let from = occurrence(&graph, &document_id, position)?
+                .map(|occurrence| occurrence.link.id)
+                .unwrap_or(document_id);
+
+            let rename = Res::<LinkIndex<LazyDocument<LocalDocumentRepository>>>::from(
+                self.cli.schema.resources(),
+            )
+            .with(|links| {
+                link::rename(
+                    &graph,
+                    links,
+                    &self.cli.document_repo,
+                    from.clone(),
+                    to.clone(),
+                )
+            })
+            .ok_or(CliError::MissingResource("link index"))??;
+
+            let mut operations = Vec::new();
+            for document in rename.references.iter().chain([&rename.node]) {
+                let Some(document) = document.try_deref() else {
+                    continue;
+                };
+
+                // The renamed document is edited under its old id, before being moved.
+                let document_id = match document.path == to {
+                    true => &from,
+                    false => &document.path,
+                };
+
+                let before = text(&graph, document_id)?;
+                let after = String::from_utf8_lossy(&document.bytes);
+                if before != after {
+                    operations.push(DocumentChangeOperation::Edit(TextDocumentEdit {
+                        text_document: OptionalVersionedTextDocumentIdentifier {
+                            uri: self.uri(document_id)?,
+                            version: None,
+                        },
+                        edits: vec![OneOf::Left(TextEdit::new(
+                            Range::new(Position::default(), end(&before)),
+                            after.into_owned(),
+                        ))],
+                    }));
+                }
+            }
+
+            operations.push(DocumentChangeOperation::Op(ResourceOp::Rename(
+                RenameFile {
+                    old_uri: self.uri(&from)?,
+                    new_uri: self.uri(&to)?,
+                    options: None,
+                    annotation_id: None,
+                },
+            )));
+
+            (rename, operations)
+        };
+
+        let from = rename.node_id.clone();
+        rename.execute(self.cli.schema.transaction())?;
+        if self.open.remove(&from) {
+            self.open.insert(to);
+        }
+
+        Ok(Some(WorkspaceEdit {
+            document_changes: Some(DocumentChanges::Operations(operations)),
+            ..Default::default()
+        }))
+    }
+
+    /// Returns the id of the document at the given URI.
+    fn id(&self, uri: &Url) -> Result<PathBuf> {
+        let not_document = || CliError::InvalidArgument(format!("{uri} is not a document"));
+        let path = uri.to_file_path().map_err(|_| not_document())?;
+        if path
+            .extension()
+            .is_none_or(|extension| extension != self.cli.document_repo.extension.as_str())
+        {
+            return Err(not_document().into());
+        }
+
+        Ok(path
+            .with_extension("")
+            .strip_prefix(&self.context)
+            .map_err(|_| not_document())?
+            .to_path_buf())
+    }
+
+    /// Returns the URI of the document with the given id.
+    fn uri(&self, document_id: &Path) -> Result<Url> {
+        let path = self
+            .context
+            .join(document_id)
+            .with_extension(&self.cli.document_repo.extension);
+
+        Url::from_file_path(&path)
+            .map_err(|_| CliError::InvalidArgument(format!("{path:?} is not absolute")).into())
+    }
+}
+
+/// Returns the diagnostics of the document with the given id.
+fn diagnostics(
+    graph: &Graph<LazyDocument<LocalDocumentRepository>>,
+    document_id: &Path,
+) -> Vec<Diagnostic> {
+    let node = graph.node(document_id.to_path_buf());
+    let Some(document) = node.try_deref().and_then(|document| document.try_deref()) else {
+        return Vec::new();
+    };
+
+    let text = String::from_utf8_lossy(&document.bytes);
+    Link::occurrences(document)
+        .into_iter()
+        .filter(|occurrence| graph.node(occurrence.link.id.clone()).is_virtual())
+        .map(|occurrence| Diagnostic {
+            range: Range::new(
+                position_of(&text, occurrence.line - 1, occurrence.range.start),
+                position_of(&text, occurrence.line - 1, occurrence.range.end),
+            ),
+            severity: Some(DiagnosticSeverity::ERROR),
+            code: Some(NumberOrString::String("dangling-link".into())),
+            source: Some(SOURCE.into()),
+            message: format!("document {:?} does not exist", occurrence.link.id),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Returns the link written at the given position of the document with the given id, if any.
+fn occurrence(
+    graph: &Graph<LazyDocument<LocalDocumentRepository>>,
+    document_id: &Path,
+    position: Position,
+) -> Result<Option<Occurrence>> {
+    let node = graph.node(document_id.to_path_buf());
+    let Some(document) = node.try_deref().and_then(|document| document.try_deref()) else {
+        return Err(CliError::NotFound(format!("{document_id:?}")).into());
+    };
+
+    let text = String::from_utf8_lossy(&document.bytes);
+    let Some((_, cursor)) = offset(&text, position) else {
+        return Ok(None);
+    };
+
+    Ok(Link::occurrences(document).into_iter().find(|occurrence| {
+        occurrence.line == position.line as usize + 1 && occurrence.range.contains(&cursor)
+    }))
+}
+
+/// Returns the text of the document with the given id.
+fn text(
+    graph: &Graph<LazyDocument<LocalDocumentRepository>>,
+    document_id: &Path,
+) -> Result<String> {
+    let node = graph.node(document_id.to_path_buf());
+    match node.try_deref().and_then(|document| document.try_deref()) {
+        Some(document) => Ok(String::from_utf8_lossy(&document.bytes).into_owned()),
+        None => Err(CliError::NotFound(format!("{document_id:?}")).into()),
+    }
+}
+
+/// Returns the line at the given position, together with the byte of that line the position
+/// points to.
+///
+/// Positions count characters in UTF-16 code units, as the protocol does by default.
+fn offset(text: &str, position: Position) -> Option<(&str, usize)> {
+    let line = text.lines().nth(position.line as usize)?;
+    let mut units = 0;
+    let byte = line
+        .char_indices()
+        .find(|(_, char)| {
+            units += char.len_utf16() as u32;
+            units > position.character
+        })
+        .map(|(byte, _)| byte)
+        .unwrap_or(line.len());
+
+    Some((line, byte))
+}
+
+/// Returns the position of the given byte of the given line, starting at 0.
+fn position_of(text: &str, line: usize, byte: usize) -> Position {
+    let character = text
+        .lines()
+        .nth(line)
+        .and_then(|line| line.get(..byte))
+        .map(utf16_len)
+        .unwrap_or_default();
+
+    Position::new(line as u32, character)
+}
+
+/// Returns the position right after the last character of the given text.
+fn end(text: &str) -> Position {
+    let line = text.split('\n').count() - 1;
+    let last = text.rsplit('\n').next().unwrap_or_default();
+    Position::new(line as u32, utf16_len(last))
+}
+
+/// Returns the length of the given text in UTF-16 code units.
+fn utf16_len(text: &str) -> u32 {
+    text.encode_utf16().count() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path, sync::Arc, thread, time::Duration};
+
+    use lsp_server::{Connection, Message, Notification, Request, RequestId};
+    use lsp_types::{
+        notification::{DidOpenTextDocument, Notification as _},
+        request::{Completion, GotoDefinition, References, Rename, Shutdown},
+        Url,
+    };
+    use plotline::{graph::Graph, schema::Schema};
+    use serde_json::{json, Value};
+
+    use crate::{link::LinkPlugin, repository::LocalDocumentRepository};
+
+    use super::LspCli;
+
+    fn recv(client: &Connection) -> Message {
+        client
+            .receiver
+            .recv_timeout(Duration::from_secs(5))
+            .expect("server should answer")
+    }
+
+    /// Sends a request to the server, returning the result of its response.
+    fn request<R: lsp_types::request::Request>(
+        client: &Connection,
+        id: i32,
+        params: Value,
+    ) -> Value {
+        client
+            .sender
+            .send(Request::new(RequestId::from(id), R::METHOD.into(), params).into())
+            .unwrap();
+
+        loop {
+            if let Message::Response(response) = recv(client) {
+                assert_eq!(response.id, RequestId::from(id));
+                return response.result.expect("request should not fail");
+            }
+        }
+    }
+
+    #[test]
+    fn documents_should_be_served_over_lsp() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = fs::canonicalize(dir.path()).unwrap();
+        let text = "# Alice\nFriend of [[bob]] and [[nobody]].\n";
+        fs::write(context.join("alice.md"), text).unwrap();
+        fs::write(context.join("bob.md"), "# Bob\n").unwrap();
+
+        let document_repo = Arc::new(LocalDocumentRepository {
+            context: context.clone(),
+            extension: "md".into(),
+        });
+
+        let cli = LspCli {
+            schema: Arc::new(
+                Schema::from(Graph::from_iter(document_repo.all()))
+                    .install(LinkPlugin::default().with_name("links"))
+                    .unwrap(),
+            ),
+            document_repo,
+        };
+
+        let uri = |name: &str| Url::from_file_path(context.join(Path::new(name))).unwrap();
+        let position = |line: u32, character: u32| {
+            json!({
+                "textDocument": { "uri": uri("alice.md") },
+                "position": { "line": line, "character": character },
+            })
+        };
+
+        let (server, client) = Connection::memory();
+        thread::scope(|scope| {
+            scope.spawn(|| cli.serve(&server).unwrap());
+
+            client
+                .sender
+                .send(
+                    Notification::new(
+                        DidOpenTextDocument::METHOD.into(),
+                        json!({ "textDocument": {
+                            "uri": uri("alice.md"),
+                            "languageId": "markdown",
+                            "version": 1,
+                            "text": text,
+                        }}),
+                    )
+                    .into(),
+                )
+                .unwrap();
+
+            let Message::Notification(notification) = recv(&client) else {
+                panic!("diagnostics should be published");
+            };
+            assert_eq!(notification.params["uri"], json!(uri("alice.md")));
+            assert_eq!(
+                notification.params["diagnostics"][0]["range"],
+                json!({
+                    "start": { "line": 1, "character": 22 },
+                    "end": { "line": 1, "character": 32 },
+                })
+            );
+
+            let completion = request::<Completion>(&client, 1, position(1, 14));
+            let labels: Vec<_> = completion
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|item| item["label"].as_str().unwrap())
+                .collect();
+            assert_eq!(labels, vec!["alice", "bob"]);
+            assert_eq!(
+                completion[1]["textEdit"]["range"]["start"],
+                json!({ "line": 1, "character": 12 })
+            );
+
+            let definition = request::<GotoDefinition>(&client, 2, position(1, 13));
+            assert_eq!(definition["uri"], json!(uri("bob.md")));
+
+            let definition = request::<GotoDefinition>(&client, 3, position(1, 25));
+            assert_eq!(
+                definition,
+                Value::Null,
+                "missing documents have no definition"
+            );
+
+            let mut params = position(0, 0);
+            params["textDocument"]["uri"] = json!(uri("bob.md"));
+            params["context"] = json!({ "includeDeclaration": false });
+            let references = request::<References>(&client, 4, params);
+            assert_eq!(
+                references,
+                json!([{
+                    "uri": uri("alice.md"),
+                    "range": {
+                        "start": { "line": 1, "character": 10 },
+                        "end": { "line": 1, "character": 17 },
+                    },
+                }])
+            );
+
+            let mut params = position(1, 13);
+            params["newName"] = json!("robert");
+            let edit = request::<Rename>(&client, 5, params);
+            let operations = edit["documentChanges"].as_array().unwrap();
+            assert_eq!(operations.len(), 2);
+            assert_eq!(
+                operations[0]["edits"][0]["newText"],
+                "# Alice\nFriend of [[robert]] and [[nobody]].\n"
+            );
+            assert_eq!(operations[1]["kind"], "rename");
+            assert_eq!(operations[1]["newUri"], json!(uri("robert.md")));
+
+            let graph = cli.schema.read();
+            assert!(!graph.node("robert".into()).is_virtual());
+            assert!(graph.node("bob".into()).is_virtual());
+            drop(graph);
+
+            request::<Shutdown>(&client, 6, Value::Null);
+            client
+                .sender
+                .send(Notification::new("exit".into(), Value::Null).into())
+                .unwrap();
+        });
+    }
+}
@@ -2,11 +2,26 @@ use std::{
     ffi::OsString,
     io,
     path::PathBuf,
+    process::ExitCode,
     sync::{Arc, LazyLock},
 };
 
-use plotline::{graph::Graph, schema::Schema};
-use plotline_cli::{document::DocumentCli, repository::LocalDocumentRepository, CliCommand};
+use plotline_cli::{
+    cache::IndexCache,
+    check::{CheckCli, CheckPlugin},
+    document::DocumentCli,
+    graph::GraphCli,
+    link::LinksCli,
+    lsp::LspCli,
+    output::{FailureRecord, Output, OutputFormat},
+    repository::LocalDocumentRepository,
+    search::SearchCli,
+    serve::ServeCli,
+    tag::TagsCli,
+    tui::TuiCli,
+    watch::DirectoryWatcher,
+    CliCommand,
+};
 use anyhow::Result;
 use clap::Parser;
 use tracing::Level;
@@ -49,33 +64,184 @@ struct Cli {
         long
     )]
     extension: String,
+
+    /// Ignore the index cache, neither reading nor updating it.
+    #[arg(global = true, long)]
+    no_cache: bool,
+
+    /// The output format.
+    ///
+    /// It is given before the command, since graph exports take their own format.
+    #[arg(long, value_enum, default_value_t)]
+    format: OutputFormat,
 }
 
-#[allow(clippy::arc_with_non_send_sync)]
-fn main() -> Result<()> {
+fn main() -> ExitCode {
     let args = Cli::parse();
 
+    // Logs would be drawn over the terminal interface.
+    let quiet = matches!(args.subcommand, CliCommand::Tui(_));
     tracing_subscriber::fmt()
         .without_time()
         .with_target(false)
         .with_max_level(Level::INFO)
-        .with_writer(io::stderr)
+        .with_writer(move || -> Box<dyn io::Write> {
+            match quiet {
+                true => Box::new(io::sink()),
+                false => Box::new(io::stderr()),
+            }
+        })
         .init();
 
+    let output = Output::from(args.format);
+    let err = match run(args, output) {
+        Ok(code) => return code,
+        Err(err) => err,
+    };
+
+    if output.format == OutputFormat::Text {
+        eprintln!("Error: {err:?}");
+    } else if let Err(err) = output.write([FailureRecord::from(&err)]) {
+        eprintln!("Error: {err:?}");
+    }
+
+    ExitCode::FAILURE
+}
+
+fn run(args: Cli, output: Output) -> Result<ExitCode> {
     let document_repo = Arc::new(LocalDocumentRepository {
         context: args.context,
         extension: args.extension,
     });
 
-    let graph = Graph::from_iter(document_repo.all());
-    let schema = Arc::new(Schema::from(graph));
-
-    let node_cli = DocumentCli {
-        schema,
-        document_repo,
+    let (schema, cache) = if args.no_cache {
+        IndexCache::rebuild(&document_repo)?
+    } else {
+        IndexCache::open(&document_repo)?
     };
 
+    let schema = Arc::new(schema.install(CheckPlugin {
+        document_repo: document_repo.clone(),
+    })?);
+
+    let mut code = ExitCode::SUCCESS;
     match args.subcommand {
-        CliCommand::Doc(command) => node_cli.execute(command),
+        CliCommand::Check(command) => {
+            let check_cli = CheckCli {
+                schema: schema.clone(),
+                document_repo,
+                output,
+            };
+
+            if !check_cli.execute(command)? {
+                code = ExitCode::FAILURE;
+            }
+        }
+        CliCommand::Doc(command) => {
+            let node_cli = DocumentCli {
+                schema: schema.clone(),
+                extension: document_repo.extension.clone(),
+                document_repo,
+                output,
+            };
+
+            node_cli.execute(command)?;
+        }
+        CliCommand::Graph(command) => {
+            let graph_cli = GraphCli {
+                schema: schema.clone(),
+                output,
+            };
+
+            graph_cli.execute(command)?;
+        }
+        CliCommand::Links(command) => {
+            let links_cli = LinksCli {
+                schema: schema.clone(),
+                output,
+            };
+
+            links_cli.links(command)?;
+        }
+        CliCommand::Backlinks(command) => {
+            let links_cli = LinksCli {
+                schema: schema.clone(),
+                output,
+            };
+
+            links_cli.backlinks(command)?;
+        }
+        CliCommand::Search(command) => {
+            let search_cli = SearchCli {
+                schema: schema.clone(),
+                output,
+            };
+
+            search_cli.execute(command)?;
+        }
+        CliCommand::Lsp(command) => {
+            // The language server runs until the client shuts it down.
+            drop(cache);
+
+            let lsp_cli = LspCli {
+                schema,
+                document_repo,
+            };
+
+            lsp_cli.execute(command)?;
+            return Ok(code);
+        }
+        CliCommand::Serve(command) => {
+            // Serving never ends either.
+            drop(cache);
+
+            let serve_cli = ServeCli {
+                schema,
+                extension: document_repo.extension.clone(),
+                document_repo,
+            };
+
+            serve_cli.execute(command)?;
+            return Ok(code);
+        }
+        CliCommand::Tags(command) => {
+            let tags_cli = TagsCli {
+                schema: schema.clone(),
+                output,
+            };
+
+            tags_cli.execute(command)?;
+        }
+        CliCommand::Tui(command) => {
+            // The interface keeps watching the directory until the user quits.
+            drop(cache);
+
+            let tui_cli = TuiCli {
+                schema,
+                document_repo,
+            };
+
+            tui_cli.execute(command)?;
+            return Ok(code);
+        }
+        CliCommand::Watch(command) => {
+            // Watching never ends, so the cache is not kept around collecting changes.
+            drop(cache);
+
+            let watcher = DirectoryWatcher {
+                schema,
+                document_repo,
+                output,
+            };
+
+            watcher.execute(command)?;
+            return Ok(code);
+        }
     }
+
+    if !args.no_cache {
+        cache.save(&schema)?;
+    }
+
+    Ok(code)
 }
@@ -0,0 +1,373 @@
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use plotline::{
+    deref::TryDeref,
+    document::{lazy::LazyDocument, DocumentRepository},
+    property::Property,
+};
+use serde::Serialize;
+
+use crate::document::Document;
+
+/// The delimiter of YAML front matter.
+const YAML_DELIMITER: &str = "---";
+/// The delimiter of TOML front matter.
+const TOML_DELIMITER: &str = "+++";
+
+/// A front matter value, regardless of the format it was written in.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    List(Vec<Value>),
+    Map(BTreeMap<String, Value>),
+}
+
+impl From<serde_yaml::Value> for Value {
+    fn from(value: serde_yaml::Value) -> Self {
+        match value {
+            serde_yaml::Value::Null => Value::Null,
+            serde_yaml::Value::Bool(value) => Value::Bool(value),
+            serde_yaml::Value::Number(number) => match number.as_i64() {
+                Some(value) => Value::Integer(value),
+                None => Value::Float(number.as_f64().unwrap_or(f64::NAN)),
+            },
+            serde_yaml::Value::String(value) => Value::String(value),
+            serde_yaml::Value::Sequence(values) => {
+                Value::List(values.into_iter().map(Into::into).collect())
+            }
+            serde_yaml::Value::Mapping(mapping) => Value::Map(
+                mapping
+                    .into_iter()
+                    .filter_map(|(key, value)| Some((Value::from(key).as_key()?, value.into())))
+                    .collect(),
+            ),
+            serde_yaml::Value::Tagged(tagged) => tagged.value.into(),
+        }
+    }
+}
+
+impl From<toml::Value> for Value {
+    fn from(value: toml::Value) -> Self {
+        match value {
+            toml::Value::String(value) => Value::String(value),
+            toml::Value::Integer(value) => Value::Integer(value),
+            toml::Value::Float(value) => Value::Float(value),
+            toml::Value::Boolean(value) => Value::Bool(value),
+            toml::Value::Datetime(value) => Value::String(value.to_string()),
+            toml::Value::Array(values) => Value::List(values.into_iter().map(Into::into).collect()),
+            toml::Value::Table(table) => Value::Map(
+                table
+                    .into_iter()
+                    .map(|(key, value)| (key, value.into()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl Value {
+    /// Returns the string slice of the value, if it is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over the value itself or, if it is a list, over its items.
+    pub fn iter(&self) -> std::slice::Iter<'_, Value> {
+        match self {
+            Value::List(values) => values.iter(),
+            value => std::slice::from_ref(value).iter(),
+        }
+    }
+
+    /// Returns the value as a map key, if it is a scalar.
+    fn as_key(&self) -> Option<String> {
+        match self {
+            Value::Bool(value) => Some(value.to_string()),
+            Value::Integer(value) => Some(value.to_string()),
+            Value::Float(value) => Some(value.to_string()),
+            Value::String(value) => Some(value.clone()),
+            Value::Null | Value::List(_) | Value::Map(_) => None,
+        }
+    }
+}
+
+/// The front matter of a document.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct Metadata {
+    values: BTreeMap<String, Value>,
+}
+
+impl Metadata {
+    /// Parses the front matter at the beginning of the given text, if any.
+    ///
+    /// Front matter delimited by `---` is parsed as YAML, while the one delimited by `+++` is
+    /// parsed as TOML. Malformed front matter is logged and treated as empty.
+    pub fn parse(text: &str) -> Self {
+        Self::try_parse(text).unwrap_or_else(|err| {
+            tracing::error!(error = err, "parsing front matter");
+            Self::default()
+        })
+    }
+
+    /// Same as [`Metadata::parse`], but failing on malformed front matter.
+    ///
+    /// Front matter is malformed if it is never closed, cannot be parsed, or is not a map.
+    pub fn try_parse(text: &str) -> Result<Self, String> {
+        let Some((delimiter, front_matter, _)) = split(text) else {
+            return match opening(text) {
+                Some(delimiter) => Err(format!("front matter is not closed by {delimiter}")),
+                None => Ok(Self::default()),
+            };
+        };
+
+        let value = match delimiter {
+            YAML_DELIMITER => serde_yaml::from_str::<serde_yaml::Value>(front_matter)
+                .map(Value::from)
+                .map_err(|err| err.to_string()),
+            _ => toml::from_str::<toml::Value>(front_matter)
+                .map(Value::from)
+                .map_err(|err| err.to_string()),
+        };
+
+        match value? {
+            Value::Map(values) => Ok(Self { values }),
+            Value::Null => Ok(Self::default()),
+            value => Err(format!("front matter is not a map: {value:?}")),
+        }
+    }
+
+    /// Returns the value of the given key, if any.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.values.get(key)
+    }
+
+    /// Returns true if, and only if, there is no metadata.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns an iterator over the keys and values of the metadata.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.values.iter()
+    }
+}
+
+/// Splits the given text into the delimiter, the content of its front matter and the remaining
+/// body, if it has front matter.
+pub(crate) fn split(text: &str) -> Option<(&'static str, &str, &str)> {
+    let delimiter = opening(text)?;
+
+    let start = text.find('\n')? + 1;
+    let mut offset = start;
+    for line in text[start..].split_inclusive('\n') {
+        if line.trim_end() == delimiter {
+            return Some((
+                delimiter,
+                &text[start..offset],
+                &text[offset + line.len()..],
+            ));
+        }
+
+        offset += line.len();
+    }
+
+    None
+}
+
+/// Returns the delimiter the given text starts with, if any.
+fn opening(text: &str) -> Option<&'static str> {
+    [YAML_DELIMITER, TOML_DELIMITER]
+        .into_iter()
+        .find(|delimiter| text.lines().next().map(str::trim_end) == Some(*delimiter))
+}
+
+/// Represents a front matter key.
+pub trait Key {
+    /// The name of the key.
+    const NAME: &'static str;
+}
+
+/// A value of the front matter key K.
+///
+/// Lists are flattened, so each item is a value on its own.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field<K> {
+    pub value: Value,
+    _key: PhantomData<fn() -> K>,
+}
+
+impl<K> Property<Document> for Field<K>
+where
+    K: Key,
+{
+    fn all(source: &Document) -> Vec<Self> {
+        source
+            .metadata()
+            .get(K::NAME)
+            .into_iter()
+            .flat_map(Value::iter)
+            .map(|value| Field {
+                value: value.clone(),
+                _key: PhantomData,
+            })
+            .collect()
+    }
+}
+
+impl<K, DocumentRepo> Property<LazyDocument<DocumentRepo>> for Field<K>
+where
+    K: Key,
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    fn all(source: &LazyDocument<DocumentRepo>) -> Vec<Self> {
+        source.try_deref().map(Field::all).unwrap_or_default()
+    }
+}
+
+/// Implements a string [`Property`] of documents taking its values from the given front matter
+/// key.
+macro_rules! string_field {
+    ($(#[$doc:meta])* $name:ident, $key:literal) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+        pub struct $name(pub String);
+
+        impl Key for $name {
+            const NAME: &'static str = $key;
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Property<Document> for $name {
+            fn all(source: &Document) -> Vec<Self> {
+                Field::<Self>::all(source)
+                    .into_iter()
+                    .filter_map(|field| field.value.as_key())
+                    .map($name)
+                    .collect()
+            }
+        }
+
+        impl<DocumentRepo> Property<LazyDocument<DocumentRepo>> for $name
+        where
+            DocumentRepo: DocumentRepository<Document = Document>,
+        {
+            fn all(source: &LazyDocument<DocumentRepo>) -> Vec<Self> {
+                source.try_deref().map($name::all).unwrap_or_default()
+            }
+        }
+    };
+}
+
+string_field!(
+    /// The title of a document.
+    Title,
+    "title"
+);
+
+string_field!(
+    /// An alternative name of a document.
+    Alias,
+    "aliases"
+);
+
+string_field!(
+    /// The id of the document right above a document in the hierarchy.
+    Parent,
+    "parent"
+);
+
+string_field!(
+    /// The date of a document, as written in its front matter.
+    Date,
+    "date"
+);
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use plotline::property::Property;
+
+    use crate::document::Document;
+
+    use super::{Alias, Date, Field, Key, Metadata, Title, Value};
+
+    fn document(text: &str) -> Document {
+        Document::new(
+            PathBuf::from("alice"),
+            "md".into(),
+            text.as_bytes().to_vec(),
+        )
+    }
+
+    struct Age;
+
+    impl Key for Age {
+        const NAME: &'static str = "age";
+    }
+
+    #[test]
+    fn yaml_front_matter_should_be_parsed() {
+        let document = document(
+            "---\ntitle: Alice\naliases: [Ally, Al]\ndate: 2024-01-31\nage: 27\n---\n# Alice\n",
+        );
+
+        assert_eq!(Title::all(&document), vec![Title("Alice".into())]);
+        assert_eq!(
+            Alias::all(&document),
+            vec![Alias("Ally".into()), Alias("Al".into())]
+        );
+        assert_eq!(Date::all(&document), vec![Date("2024-01-31".into())]);
+        assert_eq!(
+            Field::<Age>::all(&document)
+                .into_iter()
+                .map(|field| field.value)
+                .collect::<Vec<_>>(),
+            vec![Value::Integer(27)]
+        );
+        assert_eq!(document.body(), b"# Alice\n");
+    }
+
+    #[test]
+    fn toml_front_matter_should_be_parsed() {
+        let document = document("+++\ntitle = \"Alice\"\ndate = 2024-01-31\n+++\nBody");
+
+        assert_eq!(Title::all(&document), vec![Title("Alice".into())]);
+        assert_eq!(Date::all(&document), vec![Date("2024-01-31".into())]);
+        assert_eq!(document.body(), b"Body");
+    }
+
+    #[test]
+    fn missing_or_malformed_front_matter_should_be_empty() {
+        assert!(Metadata::parse("# Alice\n---\ntitle: Alice\n---\n").is_empty());
+        assert!(Metadata::parse("---\ntitle: [Alice\n---\n").is_empty());
+        assert!(Metadata::parse("---\ntitle: Alice\n").is_empty());
+        assert_eq!(document("# Alice").body(), b"# Alice");
+    }
+
+    #[test]
+    fn malformed_front_matter_should_fail() {
+        assert!(Metadata::try_parse("# Alice\n---\ntitle: Alice\n---\n").is_ok());
+        assert!(Metadata::try_parse("---\n---\n# Alice").is_ok());
+        assert!(Metadata::try_parse("---\ntitle: [Alice\n---\n").is_err());
+        assert!(Metadata::try_parse("---\n- Alice\n---\n").is_err());
+        assert_eq!(
+            Metadata::try_parse("+++\ntitle = \"Alice\"\n"),
+            Err("front matter is not closed by +++".into())
+        );
+    }
+}
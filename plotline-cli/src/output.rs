@@ -0,0 +1,192 @@
+use std::{
+    fmt::Display,
+    io::{self, Write},
+};
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::{Serialize, Serializer};
+
+use crate::error;
+
+/// The formats the output of a command can be written in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text.
+    #[default]
+    Text,
+    /// A single JSON array of records.
+    Json,
+    /// A JSON record per line.
+    Jsonl,
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.to_possible_value() {
+            Some(value) => f.write_str(value.get_name()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A piece of the output of a command.
+pub trait Record: Serialize {
+    /// Writes the record as human-readable text.
+    fn write_text(&self, writer: &mut dyn Write) -> io::Result<()>;
+}
+
+/// An error, as written by the CLI.
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorRecord {
+    /// The machine-readable kind of the error.
+    pub kind: &'static str,
+    pub message: String,
+}
+
+impl From<&anyhow::Error> for ErrorRecord {
+    fn from(err: &anyhow::Error) -> Self {
+        Self {
+            kind: error::kind(err),
+            message: err.to_string(),
+        }
+    }
+}
+
+/// The record written when a command fails.
+#[derive(Debug, Clone, Serialize)]
+pub struct FailureRecord {
+    pub error: ErrorRecord,
+}
+
+impl From<&anyhow::Error> for FailureRecord {
+    fn from(err: &anyhow::Error) -> Self {
+        Self {
+            error: ErrorRecord::from(err),
+        }
+    }
+}
+
+impl Record for FailureRecord {
+    fn write_text(&self, writer: &mut dyn Write) -> io::Result<()> {
+        writeln!(writer, "error[{}]: {}", self.error.kind, self.error.message)
+    }
+}
+
+/// Writes the records of a command into the standard output, in the chosen format.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Output {
+    pub format: OutputFormat,
+    /// Whether records are discarded instead of written into the standard output.
+    pub quiet: bool,
+}
+
+impl From<OutputFormat> for Output {
+    fn from(format: OutputFormat) -> Self {
+        Self {
+            format,
+            quiet: false,
+        }
+    }
+}
+
+impl Output {
+    /// Writes the given records into the standard output, unless the output is quiet.
+    pub fn write<R, I>(&self, records: I) -> Result<()>
+    where
+        R: Record,
+        I: IntoIterator<Item = R>,
+    {
+        if self.quiet {
+            return Ok(());
+        }
+
+        self.write_to(&mut io::stdout().lock(), records)
+    }
+
+    /// Writes the given records into the given writer.
+    ///
+    /// In the JSON format, the records of each call make up a single array.
+    pub fn write_to<W, R, I>(&self, writer: &mut W, records: I) -> Result<()>
+    where
+        W: Write,
+        R: Record,
+        I: IntoIterator<Item = R>,
+    {
+        match self.format {
+            OutputFormat::Text => {
+                for record in records {
+                    record.write_text(writer)?;
+                }
+            }
+            OutputFormat::Json => {
+                serde_json::Serializer::new(&mut *writer).collect_seq(records)?;
+                writeln!(writer)?;
+            }
+            OutputFormat::Jsonl => {
+                for record in records {
+                    serde_json::to_writer(&mut *writer, &record)?;
+                    writeln!(writer)?;
+                }
+            }
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Write};
+
+    use serde::Serialize;
+
+    use super::{Output, OutputFormat, Record};
+
+    #[derive(Serialize)]
+    struct Hit {
+        id: &'static str,
+        score: f64,
+    }
+
+    impl Record for Hit {
+        fn write_text(&self, writer: &mut dyn Write) -> io::Result<()> {
+            writeln!(writer, "{}\t{}", self.id, self.score)
+        }
+    }
+
+    fn write(format: OutputFormat) -> String {
+        let mut output = Vec::new();
+        Output::from(format)
+            .write_to(
+                &mut output,
+                [
+                    Hit {
+                        id: "alice",
+                        score: 1.5,
+                    },
+                    Hit {
+                        id: "bob",
+                        score: 0.5,
+                    },
+                ],
+            )
+            .expect("records should be written");
+
+        String::from_utf8(output).expect("output should be utf-8")
+    }
+
+    #[test]
+    fn records_should_be_written_in_every_format() {
+        assert_eq!(write(OutputFormat::Text), "alice\t1.5\nbob\t0.5\n");
+        assert_eq!(
+            write(OutputFormat::Json),
+            "[{\"id\":\"alice\",\"score\":1.5},{\"id\":\"bob\",\"score\":0.5}]\n"
+        );
+        assert_eq!(
+            write(OutputFormat::Jsonl),
+            "{\"id\":\"alice\",\"score\":1.5}\n{\"id\":\"bob\",\"score\":0.5}\n"
+        );
+    }
+}
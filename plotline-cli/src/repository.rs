@@ -1,10 +1,14 @@
-use std::{fs, path::PathBuf, sync::Arc};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use plotline::{
-    document::{lazy::LazyDocument, DocumentRepository},
+    document::{lazy::LazyDocument, DocumentRepository, Revision},
     id::Identify,
 };
-use ignore::Walk;
+use ignore::{DirEntry, Walk};
 use regex::Regex;
 
 use crate::document::Document;
@@ -19,69 +23,131 @@ pub struct LocalDocumentRepository {
 
 impl DocumentRepository for LocalDocumentRepository {
     type Document = Document;
+    type Error = io::Error;
 
-    fn find_by_id(&self, id: &<Self::Document as Identify>::Id) -> Option<Self::Document> {
-        let path = self.context.join(id).with_extension(&self.extension);
+    fn find_by_id(
+        &self,
+        id: &<Self::Document as Identify>::Id,
+    ) -> Result<Self::Document, Self::Error> {
+        fs::read(self.path(id))
+            .map(|bytes| Document::new(id.clone(), self.extension.clone(), bytes))
+    }
 
-        fs::read(&path)
-            .inspect_err(|err| {
-                tracing::error!(
-                    error = ?err,
-                    id = ?id,
-                    context = ?self.context,
-                    path = ?path,
-                    "finding document by id"
-                )
-            })
-            .map(|bytes| Document { path, bytes })
-            .ok()
+    fn revision(&self, id: &<Self::Document as Identify>::Id) -> Option<Revision> {
+        let metadata = fs::metadata(self.path(id)).ok()?;
+        Some(Revision::Modified(
+            metadata.modified().ok()?,
+            metadata.len(),
+        ))
+    }
+}
+
+/// A [`DocumentRepository`] whose documents are held by files.
+pub trait Locate: DocumentRepository {
+    /// Returns the path of the file holding the document with the given id.
+    fn locate(&self, id: &<Self::Document as Identify>::Id) -> PathBuf;
+}
+
+impl Locate for LocalDocumentRepository {
+    fn locate(&self, id: &<Self::Document as Identify>::Id) -> PathBuf {
+        self.path(id)
     }
 }
 
 impl LocalDocumentRepository {
+    /// Returns the path of the file holding the document with the given id.
+    pub fn path(&self, id: &<Document as Identify>::Id) -> PathBuf {
+        self.context.join(id).with_extension(&self.extension)
+    }
+
     /// Returns an iterator of [`LazyDocument`].
     pub fn all(self: &Arc<Self>) -> impl Iterator<Item = LazyDocument<Self>> + '_ {
-        Walk::new(&self.context)
-            .filter_map(move |entry| {
-                if let Err(err) = &entry {
-                    tracing::error!(
-                        error = err.to_string(),
-                        context = self.context.to_string_lossy().to_string(),
-                        "walking base directory"
-                    );
+        self.walk(&self.context)
+    }
+
+    /// Returns an iterator of [`LazyDocument`] in the given directory, which must be inside the
+    /// context.
+    pub fn walk<'a>(
+        self: &'a Arc<Self>,
+        root: &Path,
+    ) -> impl Iterator<Item = LazyDocument<Self>> + 'a {
+        let pattern = self.pattern();
+        self.entries(root)
+            .filter(move |entry| Self::is_document(&pattern, entry))
+            .filter_map(move |entry| self.document_id(&entry))
+            .map(LazyDocument::builder(self.clone()))
+    }
+
+    /// Walks the whole context, returning the ids of all the documents together with the path
+    /// of every directory walked, relative to the context.
+    pub fn scan(&self) -> (Vec<PathBuf>, Vec<PathBuf>) {
+        let pattern = self.pattern();
+        let mut document_ids = Vec::new();
+        let mut directories = Vec::new();
+        self.entries(&self.context).for_each(|entry| {
+            if entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_dir())
+            {
+                if let Ok(path) = entry.path().strip_prefix(&self.context) {
+                    directories.push(path.to_path_buf());
                 }
+            }
 
-                entry.ok()
-            })
-            .filter({
-                let regex = Regex::new(&format!("\\.{}$", self.extension))
-                    .expect("pattern should be a valid regular expression");
+            if Self::is_document(&pattern, &entry) {
+                document_ids.extend(self.document_id(&entry));
+            }
+        });
 
-                move |entry| {
-                    let matches = regex.is_match(&entry.file_name().to_string_lossy());
-                    tracing::debug!(path = entry.path().to_string_lossy().to_string(), matches);
+        (document_ids, directories)
+    }
 
-                    matches
-                }
-            })
-            .filter_map(move |entry| {
-                let path = entry
-                    .path()
-                    .with_extension("")
-                    .strip_prefix(&self.context)
-                    .map(ToOwned::to_owned);
-
-                if let Err(err) = &path {
-                    tracing::error!(
-                        error = err.to_string(),
-                        path = entry.path().to_string_lossy().to_string(),
-                        context = self.context.to_string_lossy().to_string(),
-                        "stripping context from path"
-                    );
-                }
+    /// Returns the pattern the file name of every document matches.
+    fn pattern(&self) -> Regex {
+        Regex::new(&format!("\\.{}$", self.extension))
+            .expect("pattern should be a valid regular expression")
+    }
 
-                path.ok()
-            })
-            .map(LazyDocument::builder(self.clone()))
+    /// Returns an iterator over the entries walked from the given directory.
+    fn entries(&self, root: &Path) -> impl Iterator<Item = DirEntry> + '_ {
+        Walk::new(root).filter_map(move |entry| {
+            if let Err(err) = &entry {
+                tracing::error!(
+                    error = err.to_string(),
+                    context = self.context.to_string_lossy().to_string(),
+                    "walking base directory"
+                );
+            }
+
+            entry.ok()
+        })
+    }
+
+    /// Returns true if, and only if, the given entry is a document.
+    fn is_document(pattern: &Regex, entry: &DirEntry) -> bool {
+        let matches = pattern.is_match(&entry.file_name().to_string_lossy());
+        tracing::debug!(path = entry.path().to_string_lossy().to_string(), matches);
+
+        matches
+    }
+
+    /// Returns the id of the document at the given entry.
+    fn document_id(&self, entry: &DirEntry) -> Option<PathBuf> {
+        let path = entry
+            .path()
+            .with_extension("")
+            .strip_prefix(&self.context)
+            .map(ToOwned::to_owned);
+
+        if let Err(err) = &path {
+            tracing::error!(
+                error = err.to_string(),
+                path = entry.path().to_string_lossy().to_string(),
+                context = self.context.to_string_lossy().to_string(),
+                "stripping context from path"
+            );
+        }
+
+        path.ok()
     }
 }
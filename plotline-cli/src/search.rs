@@ -0,0 +1,110 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use plotline::{
+    deref::{TryDeref, With},
+    document::{lazy::LazyDocument, DocumentRepository},
+    property::Property,
+    schema::{resource::Res, Schema},
+};
+use plotline_plugin_search::{Query, SearchIndex};
+use anyhow::Result;
+use clap::Args;
+use serde::Serialize;
+
+use crate::{
+    document::{Content, Document},
+    error::CliError,
+    output::{Output, Record},
+};
+
+/// The amount of characters in a snippet.
+const SNIPPET_WIDTH: usize = 80;
+
+/// Search documents by their content.
+#[derive(Args)]
+pub struct SearchCommand {
+    /// The query: words, "quoted phrases" and prefixes ending with *.
+    query: String,
+    /// The maximum amount of results.
+    #[arg(short = 'n', long, default_value_t = 10)]
+    limit: usize,
+}
+
+/// A document matching a search query.
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchRecord {
+    pub id: PathBuf,
+    /// The relevance of the document for the query.
+    pub score: f64,
+    /// The piece of the document around the first match, if any.
+    pub snippet: Option<String>,
+}
+
+impl Record for SearchRecord {
+    fn write_text(&self, writer: &mut dyn Write) -> io::Result<()> {
+        writeln!(writer, "{:?}\t{:.3}", self.id, self.score)?;
+        if let Some(snippet) = &self.snippet {
+            writeln!(writer, "    {snippet}")?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct SearchCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+    pub output: Output,
+}
+
+impl<DocumentRepo> SearchCli<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document>,
+{
+    pub fn execute(&self, command: SearchCommand) -> Result<()> {
+        self.output
+            .write(search(&self.schema, &command.query, command.limit)?)?;
+        Ok(())
+    }
+}
+
+/// Returns the documents matching the given query, best matches first.
+pub fn search<DocumentRepo>(
+    schema: &Schema<LazyDocument<DocumentRepo>>,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<SearchRecord>>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document>,
+{
+    let query = Query::parse(query);
+    let hits = Res::<SearchIndex<LazyDocument<DocumentRepo>, Content>>::from(schema.resources())
+        .with(|index| {
+            index
+                .search(&query)
+                .into_iter()
+                .take(limit)
+                .map(|hit| (hit.id.clone(), hit.score))
+                .collect::<Vec<_>>()
+        })
+        .ok_or(CliError::MissingResource("search index"))?;
+
+    let graph = schema.read();
+    let records = hits.into_iter().map(|(id, score)| {
+        let snippet = graph
+            .node(id.clone())
+            .try_deref()
+            .and_then(|document| Content::all(document).into_iter().next())
+            .and_then(|content| query.snippet(&content.0, SNIPPET_WIDTH));
+
+        SearchRecord { id, score, snippet }
+    });
+
+    Ok(records.collect())
+}
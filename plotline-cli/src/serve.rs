@@ -0,0 +1,464 @@
+use std::{
+    fmt::Display,
+    io::{self, Write},
+    path::PathBuf,
+    sync::{mpsc::RecvTimeoutError, Arc},
+    thread,
+    time::Duration,
+};
+
+use plotline::{
+    deref::{TryDeref, With},
+    document::{lazy::LazyDocument, DocumentRepository},
+    graph::Source,
+    id::Identify,
+    schema::{
+        ops::{delete::Delete, save::Save},
+        resource::Res,
+        transaction::OperationKind,
+        Schema,
+    },
+};
+use anyhow::Result;
+use clap::Args;
+use serde::Serialize;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::{
+    document::{ChangeRecord, Document, DocumentRecord},
+    error::{self, CliError},
+    link::{self, percent_decode, LinkIndex},
+    output::FailureRecord,
+    search,
+};
+
+/// How long an event stream may stay idle before a keep-alive is sent.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// The amount of links followed by default when listing links.
+const DEFAULT_DEPTH: usize = 1;
+
+/// The maximum amount of results of a search by default.
+const DEFAULT_LIMIT: usize = 10;
+
+/// Serve the documents over a local HTTP/JSON API.
+#[derive(Args)]
+pub struct ServeCommand {
+    /// The address to listen on.
+    #[arg(long, default_value = "127.0.0.1:7878")]
+    bind: String,
+}
+
+/// A document together with its content.
+#[derive(Serialize)]
+#[serde(bound = "")]
+struct DocumentBody<'a, DocumentRepo>
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+    DocumentRepo::Error: Display,
+{
+    #[serde(flatten)]
+    document: DocumentRecord<'a, DocumentRepo>,
+    content: Option<String>,
+}
+
+/// The response to a request, before it gets written.
+enum Reply {
+    Json(u16, Vec<u8>),
+    Events,
+}
+
+impl Reply {
+    fn json(status: u16, body: &impl Serialize) -> Result<Self> {
+        Ok(Self::Json(status, serde_json::to_vec(body)?))
+    }
+}
+
+pub struct ServeCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+    pub document_repo: Arc<DocumentRepo>,
+    /// The extension of the documents in the repository.
+    pub extension: String,
+}
+
+impl<DocumentRepo> ServeCli<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document> + Send + Sync,
+    DocumentRepo::Error: Display + Send + Sync,
+{
+    pub fn execute(self, command: ServeCommand) -> Result<()> {
+        let server = Server::http(&command.bind).map_err(|err| anyhow::anyhow!(err))?;
+        tracing::info!(address = server.server_addr().to_string(), "serving");
+
+        Arc::new(self).serve(&server);
+        Ok(())
+    }
+
+    /// Handles the requests to the given server, each in a thread of its own, until the server
+    /// gets unblocked.
+    pub fn serve(self: &Arc<Self>, server: &Server) {
+        for request in server.incoming_requests() {
+            let cli = self.clone();
+            thread::spawn(move || cli.handle(request));
+        }
+    }
+
+    fn handle(&self, mut request: Request) {
+        let method = request.method().clone();
+        let url = request.url().to_string();
+        tracing::debug!(method = method.to_string(), url, "handling request");
+
+        let mut body = Vec::new();
+        let reply = request
+            .as_reader()
+            .read_to_end(&mut body)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| self.route(&method, &url, body));
+
+        let result = match reply {
+            Ok(Reply::Json(status, body)) => request.respond(json(status, body)),
+            Ok(Reply::Events) => self.stream(request),
+            Err(err) => {
+                let body = serde_json::to_vec(&FailureRecord::from(&err)).unwrap_or_default();
+                request.respond(json(status(&err), body))
+            }
+        };
+
+        if let Err(err) = result {
+            tracing::debug!(error = err.to_string(), url, "writing response");
+        }
+    }
+
+    fn route(&self, method: &Method, url: &str, body: Vec<u8>) -> Result<Reply> {
+        let (path, query) = url.split_once('?').unwrap_or((url, ""));
+        let param = |name: &str| {
+            form_urlencoded::parse(query.as_bytes())
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+
+        let number = |name: &str, default: usize| {
+            param(name)
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(|_| CliError::InvalidArgument(format!("{name} must be a number")))
+                })
+                .transpose()
+                .map(|value| value.unwrap_or(default))
+        };
+
+        let segments: Vec<_> = path.trim_matches('/').splitn(2, '/').collect();
+        let id = || {
+            segments
+                .get(1)
+                .map(|id| PathBuf::from(percent_decode(id)))
+                .ok_or(CliError::InvalidArgument("document id must be set".into()))
+        };
+
+        match (method, segments[0], segments.len()) {
+            (Method::Get, "documents", 1) => {
+                let graph = self.schema.read();
+                let records: Vec<_> = graph
+                    .into_iter()
+                    .map(|document| DocumentRecord { document })
+                    .collect();
+
+                Reply::json(200, &records)
+            }
+            (Method::Get, "documents", _) => self.get(id()?),
+            (Method::Put, "documents", _) => self.save(id()?, body),
+            (Method::Delete, "documents", _) => self.delete(id()?),
+            (Method::Get, "links", _) => {
+                let (root, graph) = (id()?, self.schema.read());
+                if graph.node(root.clone()).is_virtual() {
+                    return Err(CliError::NotFound(format!("{root:?}")).into());
+                }
+
+                Reply::json(
+                    200,
+                    &link::outgoing(&graph, root, number("depth", DEFAULT_DEPTH)?),
+                )
+            }
+            (Method::Get, "backlinks", _) => {
+                let (root, graph) = (id()?, self.schema.read());
+                let depth = number("depth", DEFAULT_DEPTH)?;
+                let records =
+                    Res::<LinkIndex<LazyDocument<DocumentRepo>>>::from(self.schema.resources())
+                        .with(|links| link::incoming(&graph, links, root, depth))
+                        .ok_or(CliError::MissingResource("link index"))?;
+
+                Reply::json(200, &records)
+            }
+            (Method::Get, "search", 1) => {
+                let query = param("q").ok_or(CliError::InvalidArgument("q must be set".into()))?;
+                let limit = number("limit", DEFAULT_LIMIT)?;
+                Reply::json(200, &search::search(&self.schema, &query, limit)?)
+            }
+            (Method::Get, "events", 1) => Ok(Reply::Events),
+            _ => Err(CliError::NoRoute(format!("{method} {path}")).into()),
+        }
+    }
+
+    fn get(&self, document_id: PathBuf) -> Result<Reply> {
+        let graph = self.schema.read();
+        let Some(document) = graph.get(&document_id) else {
+            return Err(CliError::NotFound(format!("{document_id:?}")).into());
+        };
+
+        let content = document
+            .try_deref()
+            .map(|document| String::from_utf8_lossy(&document.bytes).into_owned());
+
+        Reply::json(
+            200,
+            &DocumentBody {
+                document: DocumentRecord {
+                    document: &document,
+                },
+                content,
+            },
+        )
+    }
+
+    fn save(&self, document_id: PathBuf, bytes: Vec<u8>) -> Result<Reply> {
+        let document = Document::new(document_id.clone(), self.extension.clone(), bytes);
+        Save::new(LazyDocument::new(self.document_repo.clone(), document))
+            .execute(self.schema.transaction())?;
+
+        Reply::json(
+            200,
+            &ChangeRecord {
+                id: document_id,
+                operation: "save",
+            },
+        )
+    }
+
+    fn delete(&self, document_id: PathBuf) -> Result<Reply> {
+        Delete::new(document_id.clone())
+            .execute(self.schema.transaction())
+            .map_err(|err| match err {
+                plotline::schema::Error::Noop => {
+                    CliError::NotFound(format!("{document_id:?}")).into()
+                }
+                err => anyhow::Error::new(err),
+            })?;
+
+        Reply::json(
+            200,
+            &ChangeRecord {
+                id: document_id,
+                operation: "delete",
+            },
+        )
+    }
+
+    /// Writes every change committed into the schema as a server-sent event, until the client
+    /// disconnects.
+    fn stream(&self, request: Request) -> io::Result<()> {
+        // Subscribing before answering ensures no change is missed once the client gets the
+        // headers.
+        let subscription = self.schema.subscribe();
+        let mut writer = request.into_writer();
+        write!(
+            writer,
+            "HTTP/1.1 200 OK\r\n\
+             Content-Type: text/event-stream\r\n\
+             Cache-Control: no-cache\r\n\
+             Connection: close\r\n\r\n"
+        )?;
+        writer.flush()?;
+
+        loop {
+            match subscription.recv_timeout(KEEP_ALIVE) {
+                Ok(changeset) => {
+                    for op in &changeset {
+                        let record = ChangeRecord {
+                            id: op.id().clone(),
+                            operation: match op.kind() {
+                                OperationKind::Save => "save",
+                                OperationKind::Delete => "delete",
+                            },
+                        };
+
+                        write!(writer, "event: change\ndata: ")?;
+                        serde_json::to_writer(&mut writer, &record)?;
+                        write!(writer, "\n\n")?;
+                    }
+                }
+                // Comments keep the connection alive, and tell when the client is gone.
+                Err(RecvTimeoutError::Timeout) => write!(writer, ": keep-alive\n\n")?,
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+
+            writer.flush()?;
+        }
+    }
+}
+
+/// Returns the HTTP status code corresponding to the given error.
+fn status(err: &anyhow::Error) -> u16 {
+    match error::kind(err) {
+        "invalid_argument" => 400,
+        "not_found" | "no_route" => 404,
+        // Errors raised by triggers are the schema refusing the change.
+        "schema" => 422,
+        _ => 500,
+    }
+}
+
+/// Returns a JSON response with the given status and body.
+fn json(status: u16, body: Vec<u8>) -> Response<io::Cursor<Vec<u8>>> {
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .expect("header should be valid");
+
+    Response::from_data(body)
+        .with_status_code(status)
+        .with_header(content_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::{BufRead, BufReader, Read, Write},
+        net::{SocketAddr, TcpStream},
+        path::Path,
+        sync::Arc,
+        thread,
+    };
+
+    use serde_json::Value;
+    use tiny_http::Server;
+
+    use crate::{cache::IndexCache, repository::LocalDocumentRepository};
+
+    use super::ServeCli;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    /// Sends a request to the given address, returning the status and the JSON body of the
+    /// response.
+    fn request(addr: SocketAddr, method: &str, url: &str, body: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "{method} {url} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\
+             Content-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    #[test]
+    fn documents_should_be_served() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = dir.path();
+        write(&context.join("alice.md"), "# Alice\nFriend of [[bob]].\n");
+        write(&context.join("bob.md"), "# Bob\nLives in Wonderland.\n");
+
+        let document_repo = Arc::new(LocalDocumentRepository {
+            context: context.to_path_buf(),
+            extension: "md".into(),
+        });
+
+        let (schema, _) = IndexCache::rebuild(&document_repo).unwrap();
+        let cli = Arc::new(ServeCli {
+            schema: Arc::new(schema),
+            document_repo,
+            extension: "md".into(),
+        });
+
+        let server = Arc::new(Server::http("127.0.0.1:0").unwrap());
+        let addr = server.server_addr().to_ip().unwrap();
+        let handle = thread::spawn({
+            let server = server.clone();
+            move || cli.serve(&server)
+        });
+
+        let (status, body) = request(addr, "GET", "/documents", "");
+        assert_eq!(status, 200);
+        assert_eq!(body.as_array().unwrap().len(), 2);
+
+        let (status, body) = request(addr, "GET", "/documents/bob", "");
+        assert_eq!(status, 200);
+        assert_eq!(body["content"], "# Bob\nLives in Wonderland.\n");
+
+        let (status, body) = request(addr, "GET", "/documents/nobody", "");
+        assert_eq!(status, 404);
+        assert_eq!(body["error"]["kind"], "not_found");
+
+        let (status, _) = request(addr, "GET", "/nowhere", "");
+        assert_eq!(status, 404);
+
+        let (status, body) = request(addr, "GET", "/links/alice?depth=1", "");
+        assert_eq!(status, 200);
+        assert_eq!(body[0]["id"], "bob");
+
+        let (status, body) = request(addr, "GET", "/backlinks/bob", "");
+        assert_eq!(status, 200);
+        assert_eq!(body[0]["source"], "alice");
+
+        let (status, _) = request(addr, "GET", "/links/alice?depth=many", "");
+        assert_eq!(status, 400);
+
+        let (status, body) = request(addr, "GET", "/search?q=wonderland", "");
+        assert_eq!(status, 200);
+        assert_eq!(body[0]["id"], "bob");
+
+        // Subscribe to changes before making any.
+        let mut events = TcpStream::connect(addr).unwrap();
+        write!(events, "GET /events HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut events = BufReader::new(events);
+        let mut line = String::new();
+        while line != "\r\n" {
+            line.clear();
+            events.read_line(&mut line).unwrap();
+        }
+
+        let (status, body) = request(addr, "PUT", "/documents/carol", "# Carol\n");
+        assert_eq!(status, 200);
+        assert_eq!(body["operation"], "save");
+
+        let (status, _) = request(addr, "DELETE", "/documents/bob", "");
+        assert_eq!(status, 200);
+
+        let (status, _) = request(addr, "DELETE", "/documents/bob", "");
+        assert_eq!(status, 404);
+
+        let mut event = String::new();
+        for _ in 0..3 {
+            events.read_line(&mut event).unwrap();
+        }
+        assert_eq!(
+            event,
+            "event: change\ndata: {\"id\":\"carol\",\"operation\":\"save\"}\n\n"
+        );
+
+        let (_, body) = request(addr, "GET", "/documents", "");
+        let ids: Vec<_> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|document| document["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["alice", "carol"]);
+
+        server.unblock();
+        handle.join().unwrap();
+    }
+}
@@ -0,0 +1,322 @@
+use std::{
+    cmp::Ordering,
+    collections::BTreeSet,
+    fmt::Display,
+    io::{self, Write},
+    path::PathBuf,
+    sync::{Arc, LazyLock},
+};
+
+use plotline::{
+    deref::{TryDeref, With},
+    document::{lazy::LazyDocument, DocumentRepository},
+    id::Identify,
+    prefix::Prefixed,
+    property::Property,
+    schema::{resource::Res, Schema},
+};
+use plotline_plugin_index::{Index, IndexPlugin};
+use anyhow::Result;
+use clap::Args;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    document::Document,
+    error::CliError,
+    output::{Output, Record},
+};
+
+/// Matches the tags in the body of a document.
+static TAG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|\s)#(?P<tag>[\w\-/]+)").expect("pattern should be a valid regular expression")
+});
+
+/// Matches inline code spans.
+static CODE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"`[^`]*`").expect("pattern should be a valid regular expression"));
+
+/// The front matter key holding the tags of a document.
+const TAGS_KEY: &str = "tags";
+
+/// A tag of a document, like `#character` or `#character/main`.
+///
+/// Tags are case-insensitive and ordered segment by segment, so every tag comes right before
+/// its descendants.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Tag(String);
+
+impl Ord for Tag {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.segments().cmp(other.segments())
+    }
+}
+
+impl PartialOrd for Tag {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Prefixed for Tag {
+    fn starts_with(&self, prefix: &Self) -> bool {
+        let mut segments = self.segments();
+        prefix
+            .segments()
+            .all(|segment| segments.next() == Some(segment))
+    }
+}
+
+impl Display for Tag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+impl Property<Document> for Tag {
+    fn all(source: &Document) -> Vec<Self> {
+        let front_matter = source
+            .metadata()
+            .get(TAGS_KEY)
+            .into_iter()
+            .flat_map(|value| value.iter())
+            .filter_map(|value| value.as_str())
+            .flat_map(|tags| tags.split(|c: char| c == ',' || c.is_whitespace()));
+
+        let body = String::from_utf8_lossy(source.body());
+        let mut fenced = false;
+        let body: Vec<_> = body
+            .lines()
+            .filter(|line| {
+                let trimmed = line.trim_start();
+                if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                    fenced = !fenced;
+                    return false;
+                }
+
+                !fenced
+            })
+            .flat_map(|line| {
+                let line = CODE.replace_all(line, "");
+                TAG.captures_iter(&line)
+                    .map(|captures| captures["tag"].to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        front_matter
+            .chain(body.iter().map(String::as_str))
+            .filter_map(Tag::parse)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+}
+
+impl<DocumentRepo> Property<LazyDocument<DocumentRepo>> for Tag
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    fn all(source: &LazyDocument<DocumentRepo>) -> Vec<Self> {
+        source.try_deref().map(Tag::all).unwrap_or_default()
+    }
+}
+
+impl Tag {
+    /// Returns the tag with the given name, if valid.
+    ///
+    /// The leading `#` is optional. Tags made only of digits are not valid.
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name
+            .trim()
+            .trim_start_matches('#')
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        if name.is_empty() || name.chars().all(|c| c.is_ascii_digit() || c == '/') {
+            return None;
+        }
+
+        Some(Tag(name.to_lowercase()))
+    }
+
+    /// Returns an iterator over the segments of the tag, from the root to the leaf.
+    fn segments(&self) -> impl Iterator<Item = &str> {
+        self.0.split('/')
+    }
+}
+
+/// An index of documents by tag.
+pub type TagIndex<T> = Index<T, Tag>;
+
+/// Implements the [`Plugin`](plotline::schema::plugin::Plugin) trait for a [`TagIndex`].
+pub type TagPlugin<T> = IndexPlugin<T, Tag>;
+
+/// Returns the ids of the nodes tagged with the given tag or any of its descendants.
+pub fn tagged<'a, T>(index: &'a TagIndex<T>, tag: &'a Tag) -> BTreeSet<&'a T::Id>
+where
+    T: Identify,
+    T::Id: Ord,
+{
+    index.prefix(tag).map(|(_, id)| id).collect()
+}
+
+/// List tags or the documents holding them.
+#[derive(Args)]
+pub struct TagsCommand {
+    /// The tag whose documents, including those of its descendants, are listed.
+    tag: Option<String>,
+}
+
+/// A tag and the amount of documents holding it.
+#[derive(Debug, Clone, Serialize)]
+pub struct TagRecord {
+    pub tag: Tag,
+    pub count: usize,
+}
+
+impl Record for TagRecord {
+    fn write_text(&self, writer: &mut dyn Write) -> io::Result<()> {
+        writeln!(writer, "{}\t{}", self.tag, self.count)
+    }
+}
+
+/// A document holding a tag.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaggedRecord {
+    pub id: PathBuf,
+}
+
+impl Record for TaggedRecord {
+    fn write_text(&self, writer: &mut dyn Write) -> io::Result<()> {
+        writeln!(writer, "{:?}", self.id)
+    }
+}
+
+pub struct TagsCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+    pub output: Output,
+}
+
+impl<DocumentRepo> TagsCli<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document>,
+{
+    pub fn execute(&self, command: TagsCommand) -> Result<()> {
+        let tag = command
+            .tag
+            .map(|tag| {
+                Tag::parse(&tag).ok_or(CliError::InvalidArgument(format!(
+                    "tag {tag} must be valid"
+                )))
+            })
+            .transpose()?;
+
+        let index = Res::<TagIndex<LazyDocument<DocumentRepo>>>::from(self.schema.resources());
+        match &tag {
+            Some(tag) => index
+                .with(|index| {
+                    self.output.write(
+                        tagged(index, tag)
+                            .into_iter()
+                            .map(|id| TaggedRecord { id: id.clone() }),
+                    )
+                })
+                .ok_or(CliError::MissingResource("tag index"))??,
+            None => index
+                .with(|index| {
+                    self.output
+                        .write(index.counts().map(|(tag, count)| TagRecord {
+                            tag: tag.clone(),
+                            count,
+                        }))
+                })
+                .ok_or(CliError::MissingResource("tag index"))??,
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use plotline::{id::Identify, prefix::Prefixed, property::Property};
+
+    use crate::document::Document;
+
+    use super::{tagged, Tag, TagIndex};
+
+    fn tags(text: &str) -> Vec<String> {
+        Tag::all(&Document::new(
+            PathBuf::from("alice"),
+            "md".into(),
+            text.as_bytes().to_vec(),
+        ))
+        .into_iter()
+        .map(|tag| tag.to_string())
+        .collect()
+    }
+
+    fn tag(name: &str) -> Tag {
+        Tag::parse(name).expect("tag should be valid")
+    }
+
+    #[test]
+    fn tags_should_be_extracted() {
+        assert_eq!(
+            tags(
+                "---\ntags: [Character, place/city]\n---\n\
+                 # Alice\n\
+                 A #character/Main of #2024 chapter #draft.\n\
+                 Not a#tag, nor `#code` nor [link](#anchor).\n\
+                 ```\n#ignored\n```"
+            ),
+            vec!["#character", "#character/main", "#draft", "#place/city"]
+        );
+
+        assert_eq!(tags("---\ntags: a, b\n---\n"), vec!["#a", "#b"]);
+    }
+
+    #[test]
+    fn tags_should_be_hierarchical() {
+        assert!(tag("character/main").starts_with(&tag("character")));
+        assert!(!tag("characters").starts_with(&tag("character")));
+        assert!(tag("character") < tag("character/main"));
+        assert!(tag("character/main") < tag("character-arc"));
+    }
+
+    #[test]
+    fn descendants_should_be_tagged() {
+        struct Node;
+
+        impl Identify for Node {
+            type Id = usize;
+
+            fn id(&self) -> &Self::Id {
+                &0
+            }
+        }
+
+        let mut index = TagIndex::<Node>::default();
+        index.insert(1, vec![tag("character")]);
+        index.insert(2, vec![tag("character-arc")]);
+        index.insert(3, vec![tag("character/main"), tag("character/main/hero")]);
+        index.insert(4, vec![tag("place")]);
+
+        assert_eq!(
+            tagged(&index, &tag("character"))
+                .into_iter()
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+    }
+}
@@ -0,0 +1,620 @@
+use std::{path::PathBuf, sync::Arc, thread, time::Duration};
+
+use plotline::{
+    deref::{TryDeref, With},
+    document::lazy::LazyDocument,
+    graph::Graph,
+    id::Identify,
+    property::Property,
+    schema::{resource::Res, Schema},
+};
+use anyhow::Result;
+use clap::Args;
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, ListState, Paragraph, Wrap},
+    DefaultTerminal, Frame,
+};
+
+use crate::{
+    link::{self, BacklinkRecord, LinkIndex, LinkRecord},
+    metadata::Title,
+    output::Output,
+    repository::LocalDocumentRepository,
+    watch::DirectoryWatcher,
+};
+
+type Node = LazyDocument<LocalDocumentRepository>;
+
+/// How long to wait for a key before checking for changes in the schema.
+const TICK: Duration = Duration::from_millis(100);
+
+/// The amount of lines the preview is scrolled by at once.
+const SCROLL: u16 = 10;
+
+/// The keys available while browsing.
+const HELP: &str = "/ find · tab focus · enter follow · backspace back · pgup/pgdn scroll · q quit";
+
+/// Browse the documents and their links in the terminal.
+#[derive(Args)]
+pub struct TuiCommand {
+    /// The time to wait for further changes in the directory before showing them, in
+    /// milliseconds.
+    #[arg(long, default_value_t = 200)]
+    debounce: u64,
+}
+
+pub struct TuiCli {
+    pub schema: Arc<Schema<Node>>,
+    pub document_repo: Arc<LocalDocumentRepository>,
+}
+
+impl TuiCli {
+    pub fn execute(&self, command: TuiCommand) -> Result<()> {
+        // Changes in the directory get into the schema, and from there into the interface.
+        let watcher = DirectoryWatcher {
+            schema: self.schema.clone(),
+            document_repo: self.document_repo.clone(),
+            output: Output {
+                quiet: true,
+                ..Default::default()
+            },
+        };
+
+        thread::spawn(move || {
+            if let Err(err) = watcher.watch(Duration::from_millis(command.debounce)) {
+                tracing::error!(error = format!("{err:#}"), "watching directory");
+            }
+        });
+
+        let mut terminal = ratatui::init();
+        let result = self.run(&mut terminal);
+        ratatui::restore();
+        result
+    }
+
+    /// Draws the interface and handles the keys pressed until the user quits.
+    fn run(&self, terminal: &mut DefaultTerminal) -> Result<()> {
+        let subscription = self.schema.subscribe();
+        let mut browser = Browser::new(&self.schema);
+        while !browser.quit {
+            terminal.draw(|frame| browser.render(frame, &self.schema.read()))?;
+
+            if event::poll(TICK)? {
+                if let Event::Key(key) = event::read()? {
+                    browser.handle(key, &self.schema);
+                }
+            }
+
+            let changes: usize = subscription
+                .try_iter()
+                .map(|changeset| changeset.len())
+                .sum();
+            if changes > 0 {
+                browser.refresh(&self.schema);
+                browser.status = Some(format!("{changes} document(s) changed"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The panel keys are sent to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    #[default]
+    Documents,
+    Links,
+    Backlinks,
+}
+
+impl Focus {
+    fn next(self) -> Self {
+        match self {
+            Focus::Documents => Focus::Links,
+            Focus::Links => Focus::Backlinks,
+            Focus::Backlinks => Focus::Documents,
+        }
+    }
+
+    fn previous(self) -> Self {
+        self.next().next()
+    }
+}
+
+/// The state of the interface.
+#[derive(Default)]
+struct Browser {
+    /// The ids of every document, in order.
+    ids: Vec<PathBuf>,
+    /// The query of the fuzzy finder, while it is open.
+    query: Option<String>,
+    /// The ids listed, either all of them or those matching the query.
+    listed: Vec<PathBuf>,
+    documents: ListState,
+    /// The links from the selected document.
+    links: Vec<LinkRecord>,
+    link_state: ListState,
+    /// The links to the selected document.
+    backlinks: Vec<BacklinkRecord>,
+    backlink_state: ListState,
+    focus: Focus,
+    /// The documents left by following links, to go back to.
+    history: Vec<PathBuf>,
+    /// The amount of lines the preview is scrolled by.
+    scroll: u16,
+    /// The message shown instead of the help, if any.
+    status: Option<String>,
+    quit: bool,
+}
+
+impl Browser {
+    fn new(schema: &Schema<Node>) -> Self {
+        let mut browser = Self::default();
+        browser.refresh(schema);
+        browser
+    }
+
+    /// Returns the id of the selected document, if any.
+    fn selected(&self) -> Option<&PathBuf> {
+        self.listed.get(self.documents.selected()?)
+    }
+
+    /// Reloads the documents from the given schema, keeping the selected one if it still exists.
+    fn refresh(&mut self, schema: &Schema<Node>) {
+        let selected = self.selected().cloned();
+        self.ids = schema
+            .read()
+            .into_iter()
+            .map(|document| document.id().clone())
+            .collect();
+
+        self.filter();
+        match selected {
+            Some(selected) => self.select(selected, schema),
+            None => self.load(schema),
+        }
+    }
+
+    /// Lists the documents matching the query, best matches first.
+    fn filter(&mut self) {
+        self.listed = match &self.query {
+            Some(query) => {
+                let mut matches: Vec<_> = self
+                    .ids
+                    .iter()
+                    .filter_map(|id| Some((fuzzy(query, &id.to_string_lossy())?, id)))
+                    .collect();
+
+                matches.sort_by(|(a, _), (b, _)| b.cmp(a));
+                matches.into_iter().map(|(_, id)| id.clone()).collect()
+            }
+            None => self.ids.clone(),
+        };
+
+        self.documents
+            .select((!self.listed.is_empty()).then_some(0));
+    }
+
+    /// Selects the document with the given id, closing the finder if it is not listed.
+    fn select(&mut self, id: PathBuf, schema: &Schema<Node>) {
+        if !self.listed.contains(&id) {
+            self.query = None;
+            self.filter();
+        }
+
+        if let Some(index) = self.listed.iter().position(|listed| listed == &id) {
+            self.documents.select(Some(index));
+        }
+
+        self.load(schema);
+    }
+
+    /// Loads the links from and to the selected document.
+    fn load(&mut self, schema: &Schema<Node>) {
+        let graph = schema.read();
+        (self.links, self.backlinks) = match self.selected() {
+            Some(id) => (
+                link::outgoing(&graph, id.clone(), 1),
+                Res::<LinkIndex<Node>>::from(schema.resources())
+                    .with(|links| link::incoming(&graph, links, id.clone(), 1))
+                    .unwrap_or_default(),
+            ),
+            None => Default::default(),
+        };
+
+        self.link_state
+            .select((!self.links.is_empty()).then_some(0));
+        self.backlink_state
+            .select((!self.backlinks.is_empty()).then_some(0));
+        self.scroll = 0;
+    }
+
+    fn handle(&mut self, key: KeyEvent, schema: &Schema<Node>) {
+        if key.kind != KeyEventKind::Press {
+            return;
+        }
+
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+
+        self.status = None;
+        if let Some(query) = &mut self.query {
+            match key.code {
+                KeyCode::Char(char) => query.push(char),
+                KeyCode::Backspace => {
+                    query.pop();
+                }
+                KeyCode::Up => return self.step(-1, schema),
+                KeyCode::Down => return self.step(1, schema),
+                KeyCode::Enter | KeyCode::Esc => {
+                    // Closing the finder keeps the selected match, if any.
+                    let selected = self.selected().cloned();
+                    self.query = None;
+                    self.filter();
+                    match selected {
+                        Some(selected) => self.select(selected, schema),
+                        None => self.load(schema),
+                    }
+
+                    return;
+                }
+                _ => return,
+            }
+
+            self.filter();
+            return self.load(schema);
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('/') => {
+                self.query = Some(String::new());
+                self.focus = Focus::Documents;
+            }
+            KeyCode::Tab => self.focus = self.focus.next(),
+            KeyCode::BackTab => self.focus = self.focus.previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.step(1, schema),
+            KeyCode::Up | KeyCode::Char('k') => self.step(-1, schema),
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => self.follow(schema),
+            KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') => {
+                if let Some(id) = self.history.pop() {
+                    self.select(id, schema);
+                }
+            }
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(SCROLL),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(SCROLL),
+            _ => {}
+        }
+    }
+
+    /// Moves the selection of the focused panel by the given amount of items.
+    fn step(&mut self, delta: isize, schema: &Schema<Node>) {
+        let (state, len) = match self.focus {
+            Focus::Documents => (&mut self.documents, self.listed.len()),
+            Focus::Links => (&mut self.link_state, self.links.len()),
+            Focus::Backlinks => (&mut self.backlink_state, self.backlinks.len()),
+        };
+
+        let Some(selected) = state.selected() else {
+            return;
+        };
+
+        state.select(Some(
+            selected
+                .saturating_add_signed(delta)
+                .min(len.saturating_sub(1)),
+        ));
+
+        if self.focus == Focus::Documents {
+            self.load(schema);
+        }
+    }
+
+    /// Selects the document at the other end of the selected link.
+    fn follow(&mut self, schema: &Schema<Node>) {
+        let target = match self.focus {
+            Focus::Documents => {
+                self.focus = Focus::Links;
+                return;
+            }
+            Focus::Links => self
+                .link_state
+                .selected()
+                .and_then(|index| self.links.get(index))
+                .map(|record| record.link.id.clone()),
+            Focus::Backlinks => self
+                .backlink_state
+                .selected()
+                .and_then(|index| self.backlinks.get(index))
+                .map(|record| record.source.clone()),
+        };
+
+        let (Some(target), Some(current)) = (target, self.selected().cloned()) else {
+            return;
+        };
+
+        if schema.read().node(target.clone()).is_virtual() {
+            self.status = Some(format!("document {target:?} does not exist"));
+            return;
+        }
+
+        self.history.push(current);
+        self.select(target, schema);
+    }
+
+    fn render(&mut self, frame: &mut Frame, graph: &Graph<Node>) {
+        let [main, footer] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [side, right] =
+            Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)])
+                .areas(main);
+        let [preview, edges] =
+            Layout::vertical([Constraint::Percentage(65), Constraint::Percentage(35)]).areas(right);
+        let [links, backlinks] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(edges);
+
+        let title = match &self.query {
+            Some(query) => format!(" Find: {query} "),
+            None => format!(" Documents ({}) ", self.ids.len()),
+        };
+
+        let items = self
+            .listed
+            .iter()
+            .map(|id| id.to_string_lossy().into_owned());
+        frame.render_stateful_widget(
+            list(items, panel(&title, self.focus == Focus::Documents)),
+            side,
+            &mut self.documents,
+        );
+
+        self.render_preview(frame, preview, graph);
+
+        let items = self.links.iter().map(|record| {
+            let id = Span::raw(record.link.id.to_string_lossy().into_owned());
+            match record.is_virtual {
+                true => Line::from(vec![id.fg(Color::Red), " (virtual)".dark_gray()]),
+                false => Line::from(id),
+            }
+        });
+
+        let title = format!(" Links ({}) ", self.links.len());
+        frame.render_stateful_widget(
+            list(items, panel(&title, self.focus == Focus::Links)),
+            links,
+            &mut self.link_state,
+        );
+
+        let items = self.backlinks.iter().map(|record| {
+            Line::from(vec![
+                format!("{}:{} ", record.source.to_string_lossy(), record.line).into(),
+                record.context.clone().dark_gray(),
+            ])
+        });
+
+        let title = format!(" Backlinks ({}) ", self.backlinks.len());
+        frame.render_stateful_widget(
+            list(items, panel(&title, self.focus == Focus::Backlinks)),
+            backlinks,
+            &mut self.backlink_state,
+        );
+
+        let status = match &self.status {
+            Some(status) => Line::from(status.as_str()).yellow(),
+            None => Line::from(HELP).dark_gray(),
+        };
+
+        frame.render_widget(status, footer);
+    }
+
+    fn render_preview(&self, frame: &mut Frame, area: Rect, graph: &Graph<Node>) {
+        let node = self.selected().map(|id| graph.node(id.clone()));
+        let document = node
+            .as_ref()
+            .and_then(|node| node.try_deref())
+            .and_then(|document| document.try_deref());
+
+        let Some(document) = document else {
+            frame.render_widget(panel(" Preview ", false), area);
+            return;
+        };
+
+        let path = document.path.to_string_lossy();
+        let title = match Title::all(document).into_iter().next() {
+            Some(title) => format!(" {} · {path} ", title.0),
+            None => format!(" {path} "),
+        };
+
+        let preview = Paragraph::new(String::from_utf8_lossy(&document.bytes).into_owned())
+            .block(panel(&title, false))
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+
+        frame.render_widget(preview, area);
+    }
+}
+
+/// Returns the list widget of a panel.
+fn list<'a, T>(items: impl IntoIterator<Item = T>, block: Block<'a>) -> List<'a>
+where
+    T: Into<ListItem<'a>>,
+{
+    List::new(items)
+        .block(block)
+        .highlight_style(Style::new().reversed())
+        .highlight_symbol("> ")
+}
+
+/// Returns the bordered block of a panel, highlighted if it is focused.
+fn panel(title: &str, focused: bool) -> Block<'_> {
+    let block = Block::bordered().title(title);
+    match focused {
+        true => block.border_style(Style::new().fg(Color::Yellow)),
+        false => block,
+    }
+}
+
+/// Returns the score of the given text for the given query, if every character of the query is
+/// found in the text, in order and regardless of the case.
+///
+/// Consecutive characters and those at the start of a word score higher.
+fn fuzzy(query: &str, text: &str) -> Option<usize> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    let Some(first) = query.first() else {
+        return Some(0);
+    };
+
+    // The best match may start at any occurrence of the first character.
+    text.char_indices()
+        .filter(|(_, char)| char.to_lowercase().eq([*first]))
+        .filter_map(|(start, _)| score(&query, text, start))
+        .max()
+}
+
+/// Returns the score of matching the given query into the given text from the given byte on.
+fn score(query: &[char], text: &str, start: usize) -> Option<usize> {
+    let mut score = 0;
+    // The byte right after the previous match.
+    let mut next = None;
+    let mut chars = text[start..]
+        .char_indices()
+        .map(|(index, char)| (start + index, char));
+
+    for wanted in query {
+        let (index, found) = chars
+            .by_ref()
+            .find(|(_, char)| char.to_lowercase().eq([*wanted]))?;
+
+        score += 1;
+        if next == Some(index) {
+            score += 2;
+        }
+
+        let boundary = text[..index]
+            .chars()
+            .next_back()
+            .is_none_or(|char| matches!(char, '/' | '-' | '_' | ' ' | '.'));
+        if boundary {
+            score += 3;
+        }
+
+        next = Some(index + found.len_utf8());
+    }
+
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf, sync::Arc};
+
+    use plotline::{graph::Graph, schema::Schema};
+    use ratatui::{
+        backend::TestBackend,
+        crossterm::event::{KeyCode, KeyEvent},
+        Terminal,
+    };
+
+    use crate::{link::LinkPlugin, repository::LocalDocumentRepository};
+
+    use super::{fuzzy, Browser, Focus};
+
+    #[test]
+    fn fuzzy_matches_should_be_ranked() {
+        assert!(fuzzy("xz", "characters/alice").is_none());
+        assert!(fuzzy("ecila", "characters/alice").is_none());
+        assert!(
+            fuzzy("Al", "characters/alice") > fuzzy("al", "places/hall"),
+            "matches at the start of a word should rank higher"
+        );
+        assert!(
+            fuzzy("bob", "characters/bob") > fuzzy("bob", "characters/boomb"),
+            "consecutive matches should rank higher"
+        );
+    }
+
+    #[test]
+    fn links_should_be_followed() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = dir.path();
+        fs::write(context.join("alice.md"), "# Alice\nFriend of [[bob]].\n").unwrap();
+        fs::write(
+            context.join("bob.md"),
+            "# Bob\nBrother of [[carol]] and [[dave]].\n",
+        )
+        .unwrap();
+        fs::write(context.join("carol.md"), "# Carol\n").unwrap();
+
+        let document_repo = Arc::new(LocalDocumentRepository {
+            context: context.to_path_buf(),
+            extension: "md".into(),
+        });
+
+        let schema = Schema::from(Graph::from_iter(document_repo.all()))
+            .install(LinkPlugin::default().with_name("links"))
+            .unwrap();
+
+        let mut browser = Browser::new(&schema);
+        let press = |browser: &mut Browser, code| browser.handle(KeyEvent::from(code), &schema);
+        let selected = |browser: &Browser| browser.selected().cloned();
+
+        assert_eq!(selected(&browser), Some(PathBuf::from("alice")));
+
+        press(&mut browser, KeyCode::Enter);
+        assert_eq!(browser.focus, Focus::Links);
+        press(&mut browser, KeyCode::Enter);
+        assert_eq!(selected(&browser), Some(PathBuf::from("bob")));
+
+        press(&mut browser, KeyCode::Down);
+        press(&mut browser, KeyCode::Enter);
+        assert_eq!(
+            selected(&browser),
+            Some(PathBuf::from("bob")),
+            "missing documents should not be followed"
+        );
+        assert!(browser.status.is_some());
+
+        press(&mut browser, KeyCode::Tab);
+        press(&mut browser, KeyCode::Enter);
+        assert_eq!(selected(&browser), Some(PathBuf::from("alice")));
+
+        press(&mut browser, KeyCode::Backspace);
+        press(&mut browser, KeyCode::Backspace);
+        assert_eq!(selected(&browser), Some(PathBuf::from("alice")));
+
+        press(&mut browser, KeyCode::Char('/'));
+        for char in "crl".chars() {
+            press(&mut browser, KeyCode::Char(char));
+        }
+        assert_eq!(browser.listed, vec![PathBuf::from("carol")]);
+        press(&mut browser, KeyCode::Enter);
+        assert_eq!(browser.query, None);
+        assert_eq!(browser.listed.len(), 3);
+        assert_eq!(selected(&browser), Some(PathBuf::from("carol")));
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal
+            .draw(|frame| browser.render(frame, &schema.read()))
+            .unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+
+        assert!(screen.contains("Documents (3)"));
+        assert!(screen.contains("# Carol"));
+        assert!(screen.contains("bob:2"));
+    }
+}
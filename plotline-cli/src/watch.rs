@@ -0,0 +1,389 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc},
+    time::{Duration, Instant},
+};
+
+use plotline::{
+    document::lazy::LazyDocument,
+    id::Identify,
+    schema::{
+        ops::{delete::Delete, save::Save},
+        Schema,
+    },
+};
+use anyhow::Result;
+use clap::Args;
+use ignore::gitignore::Gitignore;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+use crate::{
+    document::ChangeRecord,
+    output::{FailureRecord, Output, OutputFormat},
+    repository::LocalDocumentRepository,
+};
+
+/// Keep the graph in sync with the changes made to the base directory.
+#[derive(Args)]
+pub struct WatchCommand {
+    /// The time to wait for further changes since the first one before applying them, in
+    /// milliseconds.
+    #[arg(long, default_value_t = 200)]
+    debounce: u64,
+}
+
+/// Turns the changes made to the directory of a [`LocalDocumentRepository`] into schema
+/// operations.
+pub struct DirectoryWatcher {
+    pub schema: Arc<Schema<LazyDocument<LocalDocumentRepository>>>,
+    pub document_repo: Arc<LocalDocumentRepository>,
+    pub output: Output,
+}
+
+impl DirectoryWatcher {
+    pub fn execute(&self, command: WatchCommand) -> Result<()> {
+        self.watch(Duration::from_millis(command.debounce))
+    }
+
+    /// Blocks the current thread applying every change made to the base directory.
+    ///
+    /// Changes are applied in batches, each holding the changes made during the given timeout
+    /// since the first of them. Hence, a steady stream of changes does not delay them forever.
+    pub fn watch(&self, timeout: Duration) -> Result<()> {
+        let context = fs::canonicalize(&self.document_repo.context)?;
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        watcher.watch(&context, RecursiveMode::Recursive)?;
+
+        // Accesses are skipped since they are caused by reading the directory, including the
+        // reads of the watcher itself.
+        let changed_paths = |event: notify::Result<Event>| match event {
+            Ok(event) if !matches!(event.kind, EventKind::Access(_)) => event
+                .paths
+                .into_iter()
+                .filter_map(|path| {
+                    path.strip_prefix(&context)
+                        .map(|path| self.document_repo.context.join(path))
+                        .ok()
+                })
+                .collect(),
+            Ok(_) => Vec::default(),
+            Err(err) => {
+                tracing::error!(error = err.to_string(), "watching context");
+                Vec::default()
+            }
+        };
+
+        tracing::info!(context = context.to_string_lossy().to_string(), "watching");
+        while let Ok(event) = receiver.recv() {
+            let mut paths = changed_paths(event);
+            let deadline = Instant::now() + timeout;
+            while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+                match receiver.recv_timeout(remaining) {
+                    Ok(event) => paths.extend(changed_paths(event)),
+                    Err(_) => break,
+                }
+            }
+
+            self.sync(paths);
+        }
+
+        Ok(())
+    }
+
+    /// Saves or deletes the documents at the given paths according to the current state of the
+    /// file-system.
+    ///
+    /// Existing files are saved if they are walked by [`LocalDocumentRepository::all`], while
+    /// missing ones are deleted. Directories are handled as if each of their files changed.
+    pub fn sync(&self, paths: impl IntoIterator<Item = PathBuf>) {
+        for path in paths.into_iter().collect::<BTreeSet<_>>() {
+            if path.is_dir() {
+                if self.is_walked(&path) {
+                    self.document_repo
+                        .walk(&path)
+                        .for_each(|document| self.save(document));
+                }
+            } else if path.exists() {
+                if let Some(document_id) = self.document_id(&path).filter(|_| self.is_walked(&path))
+                {
+                    self.save(LazyDocument::builder(self.document_repo.clone())(
+                        document_id,
+                    ));
+                }
+            } else if let Ok(prefix) = path.strip_prefix(&self.document_repo.context) {
+                // The path may have been either a document or a whole directory.
+                let document_ids: Vec<_> = self
+                    .schema
+                    .read()
+                    .into_iter()
+                    .map(|document| document.id().clone())
+                    .filter(|document_id| {
+                        Some(document_id) == self.document_id(&path).as_ref()
+                            || (document_id != prefix && document_id.starts_with(prefix))
+                    })
+                    .collect();
+
+                document_ids
+                    .into_iter()
+                    .for_each(|document_id| self.delete(document_id));
+            }
+        }
+    }
+
+    fn save(&self, document: LazyDocument<LocalDocumentRepository>) {
+        let document_id = document.id().clone();
+        let result = Save::new(document).execute(self.schema.transaction());
+        self.report(document_id, "save", result);
+    }
+
+    fn delete(&self, document_id: PathBuf) {
+        let result = Delete::new(document_id.clone()).execute(self.schema.transaction());
+        self.report(document_id, "delete", result);
+    }
+
+    /// Logs the result of the given operation and writes it into the output.
+    fn report(
+        &self,
+        document_id: PathBuf,
+        operation: &'static str,
+        result: plotline::schema::Result<()>,
+    ) {
+        let record = match result {
+            Ok(()) => {
+                tracing::info!(id = ?document_id, operation, "document changed");
+                self.output.write([ChangeRecord {
+                    id: document_id,
+                    operation,
+                }])
+            }
+            Err(err) => {
+                tracing::error!(id = ?document_id, error = ?err, operation, "changing document");
+                if self.output.format == OutputFormat::Text {
+                    return;
+                }
+
+                self.output
+                    .write([FailureRecord::from(&anyhow::Error::new(err))])
+            }
+        };
+
+        if let Err(err) = record {
+            tracing::error!(error = ?err, "writing output");
+        }
+    }
+
+    /// Returns the id of the document stored at the given path, if any.
+    fn document_id(&self, path: &Path) -> Option<PathBuf> {
+        if path.extension()? != self.document_repo.extension.as_str() {
+            return None;
+        }
+
+        path.with_extension("")
+            .strip_prefix(&self.document_repo.context)
+            .map(ToOwned::to_owned)
+            .ok()
+    }
+
+    /// Returns true if, and only if, the given path would be walked from the base directory.
+    ///
+    /// The path is matched against the same rules as [`ignore::Walk`]: hidden entries are
+    /// skipped, and so are those ignored by the `.ignore` files, or the `.gitignore` ones within
+    /// a git repository, of any directory on the way.
+    fn is_walked(&self, path: &Path) -> bool {
+        let context = &self.document_repo.context;
+        let Ok(relative) = path.strip_prefix(context) else {
+            return false;
+        };
+
+        let in_repository = context.ancestors().any(|dir| dir.join(".git").exists());
+        let mut matchers = Vec::new();
+        let mut parent = context.clone();
+        relative.components().all(|component| {
+            if component.as_os_str().to_string_lossy().starts_with('.') {
+                return false;
+            }
+
+            // Deeper rules take precedence, as do .ignore files over .gitignore ones.
+            if in_repository {
+                matchers.push(Gitignore::new(parent.join(".gitignore")).0);
+            }
+
+            matchers.push(Gitignore::new(parent.join(".ignore")).0);
+
+            let child = parent.join(component);
+            let ignored = matchers
+                .iter()
+                .rev()
+                .map(|matcher| matcher.matched(&child, child.is_dir()))
+                .find(|matched| !matched.is_none())
+                .is_some_and(|matched| matched.is_ignore());
+
+            parent = child;
+            !ignored
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+        sync::Arc,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use plotline::{graph::Graph, id::Identify, schema::Schema};
+
+    use crate::{output::Output, repository::LocalDocumentRepository};
+
+    use super::DirectoryWatcher;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn watcher(context: &Path) -> DirectoryWatcher {
+        let document_repo = Arc::new(LocalDocumentRepository {
+            context: context.to_path_buf(),
+            extension: "md".into(),
+        });
+
+        DirectoryWatcher {
+            schema: Arc::new(Schema::from(Graph::from_iter(document_repo.all()))),
+            document_repo,
+            output: Output {
+                quiet: true,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn ids(watcher: &DirectoryWatcher) -> Vec<String> {
+        watcher
+            .schema
+            .read()
+            .into_iter()
+            .map(|document| document.id().to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn changes_should_be_synced() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = dir.path().to_path_buf();
+        fs::create_dir(context.join(".git")).unwrap();
+        write(&context.join(".gitignore"), "drafts/\n");
+        write(&context.join(".ignore"), "ignored/\n");
+        write(&context.join("alice.md"), "Alice");
+        write(&context.join("bob.md"), "Bob");
+
+        let watcher = watcher(&context);
+
+        assert_eq!(ids(&watcher), vec!["alice", "bob"]);
+
+        // Creations, including ignored, hidden and foreign files.
+        let created: Vec<PathBuf> = [
+            "places/city.md",
+            "ignored/secret.md",
+            "drafts/secret.md",
+            ".hidden/secret.md",
+            "notes.txt",
+        ]
+        .into_iter()
+        .map(|path| context.join(path))
+        .collect();
+
+        created.iter().for_each(|path| write(path, "content"));
+        watcher.sync(created);
+        assert_eq!(ids(&watcher), vec!["alice", "bob", "places/city"]);
+
+        // Renames and deletions.
+        fs::rename(context.join("alice.md"), context.join("carol.md")).unwrap();
+        fs::remove_dir_all(context.join("places")).unwrap();
+        watcher.sync(["alice.md", "carol.md", "places"].map(|path| context.join(path)));
+        assert_eq!(ids(&watcher), vec!["bob", "carol"]);
+
+        // Moved in directories.
+        write(&context.join("chapters/one.md"), "One");
+        write(&context.join("chapters/two.md"), "Two");
+        watcher.sync([context.join("chapters")]);
+        assert_eq!(
+            ids(&watcher),
+            vec!["bob", "carol", "chapters/one", "chapters/two"]
+        );
+    }
+
+    #[test]
+    fn removed_directories_should_keep_documents_named_alike() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = dir.path().to_path_buf();
+        write(&context.join("chapters.md"), "Chapters");
+        write(&context.join("chapters/one.md"), "One");
+        write(&context.join("alice.md"), "Alice");
+        write(&context.join("alice"), "Not a document");
+
+        let watcher = watcher(&context);
+        assert_eq!(ids(&watcher), vec!["alice", "chapters", "chapters/one"]);
+
+        fs::remove_dir_all(context.join("chapters")).unwrap();
+        fs::remove_file(context.join("alice")).unwrap();
+        watcher.sync(["chapters", "alice"].map(|path| context.join(path)));
+        assert_eq!(ids(&watcher), vec!["alice", "chapters"]);
+    }
+
+    #[test]
+    fn watched_changes_should_be_synced() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = dir.path().to_path_buf();
+        write(&context.join("alice.md"), "Alice");
+
+        let watcher = watcher(&context);
+
+        let schema = watcher.schema.clone();
+        let synced = |want: Vec<&str>| {
+            let deadline = Instant::now() + Duration::from_secs(10);
+            while Instant::now() < deadline {
+                let got: Vec<_> = schema
+                    .read()
+                    .into_iter()
+                    .map(|document| document.id().to_string_lossy().to_string())
+                    .collect();
+
+                if got == want {
+                    return;
+                }
+
+                thread::sleep(Duration::from_millis(10));
+            }
+
+            panic!("changes should be synced into {want:?}");
+        };
+
+        // The watcher runs until the process exits, since it never stops by itself.
+        thread::spawn(move || watcher.watch(Duration::from_millis(100)));
+
+        // Keep writing faster than the timeout, since changes made before the watcher starts are
+        // missed. This also checks a steady stream of changes does not delay them forever.
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !schema
+            .read()
+            .into_iter()
+            .any(|document| document.id() == Path::new("bob"))
+        {
+            assert!(Instant::now() < deadline, "created files should be synced");
+            write(&context.join("bob.md"), "Bob");
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        synced(vec!["alice", "bob"]);
+
+        fs::remove_file(context.join("alice.md")).unwrap();
+        synced(vec!["bob"]);
+    }
+}
@@ -0,0 +1,62 @@
+//! Derive implementation for the `Identify` trait.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{spanned::Spanned, Data, DeriveInput, Error, Fields, Result};
+
+/// Returns the implementation of the `Identify` trait for the given input.
+pub fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            input.span(),
+            "Identify can only be derived for structs",
+        ));
+    };
+
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new(
+            data.fields.span(),
+            "Identify can only be derived for structs with named fields",
+        ));
+    };
+
+    let mut annotated = fields
+        .named
+        .iter()
+        .filter(|field| field.attrs.iter().any(|attr| attr.path().is_ident("id")));
+
+    let id_field = match (annotated.next(), annotated.next()) {
+        (Some(field), None) => field,
+        (Some(_), Some(field)) => {
+            return Err(Error::new(
+                field.span(),
+                "only one field can be annotated with #[id]",
+            ))
+        }
+        (None, _) => fields
+            .named
+            .iter()
+            .find(|field| field.ident.as_ref().is_some_and(|ident| ident == "id"))
+            .ok_or_else(|| {
+                Error::new(
+                    fields.span(),
+                    "missing #[id] annotation in any of the struct fields",
+                )
+            })?,
+    };
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    let id_ident = &id_field.ident;
+    let id_type = &id_field.ty;
+
+    Ok(quote! {
+        impl #impl_generics ::plotline::id::Identify for #name #type_generics #where_clause {
+            type Id = #id_type;
+
+            fn id(&self) -> &Self::Id {
+                &self.#id_ident
+            }
+        }
+    })
+}
@@ -0,0 +1,224 @@
+//! Procedural macros for plotline.
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput, ItemFn};
+
+mod identify;
+mod property;
+mod trigger;
+
+/// Implements the `Identify` trait for a struct.
+///
+/// The field holding the id must be annotated with `#[id]`. If no field is annotated, the field
+/// named `id` is used instead.
+///
+/// ```
+/// use plotline::id::Identify;
+///
+/// #[derive(Identify)]
+/// struct Character {
+///     #[id]
+///     name: String,
+/// }
+///
+/// let alice = Character {
+///     name: "Alice".into(),
+/// };
+///
+/// assert_eq!(alice.id(), "Alice");
+/// ```
+#[proc_macro_derive(Identify, attributes(id))]
+pub fn derive_identify(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    identify::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements the `Property` trait of a struct for the types given in the `#[property(...)]`
+/// annotations of its fields.
+///
+/// Annotated fields must be iterable by reference, like a `Vec` or an `Option`, and their items
+/// must be convertible into the property type. Fields annotated with the same property type are
+/// chained in declaration order.
+///
+/// ```
+/// use plotline::{id::Identify, property::Property};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Friend(String);
+///
+/// impl From<String> for Friend {
+///     fn from(name: String) -> Self {
+///         Friend(name)
+///     }
+/// }
+///
+/// #[derive(Identify, Property)]
+/// struct Character {
+///     #[id]
+///     name: String,
+///     #[property(Friend)]
+///     friends: Vec<String>,
+/// }
+///
+/// let alice = Character {
+///     name: "Alice".into(),
+///     friends: vec!["Bob".into()],
+/// };
+///
+/// assert_eq!(Friend::all(&alice), vec![Friend("Bob".into())]);
+/// ```
+#[proc_macro_derive(Property, attributes(property))]
+pub fn derive_property(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    property::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Turns a function into a trigger scheduled for the given schedulers.
+///
+/// The function becomes a unit struct implementing the `Plugin` trait, so it gets registered
+/// by installing it into the schema. The node type is taken from the function's first argument,
+/// which must be a `Ctx`.
+///
+/// ```
+/// use plotline::{
+///     graph::Graph,
+///     prelude::*,
+///     schema::{ops::save::Save, trigger::trigger},
+/// };
+///
+/// #[derive(Clone, Identify)]
+/// struct Character {
+///     #[id]
+///     name: String,
+/// }
+///
+/// #[derive(Default)]
+/// struct Counter(usize);
+///
+/// #[trigger(AfterSave, AfterDelete)]
+/// fn count_changes(_: Ctx<Character>, counter: Res<Counter>) -> Result<()> {
+///     counter.with_mut(|counter| counter.0 += 1);
+///     Ok(())
+/// }
+///
+/// let schema = Schema::from(Graph::default())
+///     .with_resource(Counter::default())
+///     .install(count_changes)?;
+///
+/// Save::new(Character {
+///     name: "Alice".into(),
+/// })
+/// .execute(schema.transaction())?;
+///
+/// let count = Res::<Counter>::from(schema.resources()).with(|counter| counter.0);
+/// assert_eq!(count, Some(1));
+/// # Ok::<(), plotline::schema::Error>(())
+/// ```
+#[proc_macro_attribute]
+pub fn trigger(args: TokenStream, input: TokenStream) -> TokenStream {
+    let schedulers = parse_macro_input!(args as trigger::Schedulers);
+    let input = parse_macro_input!(input as ItemFn);
+    trigger::expand(schedulers, input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Invalid usages of the macros, each of them must fail to compile.
+///
+/// A struct without any field annotated with `#[id]` nor named `id`:
+///
+/// ```compile_fail
+/// use plotline::id::Identify;
+///
+/// #[derive(Identify)]
+/// struct Character {
+///     name: String,
+/// }
+/// ```
+///
+/// A struct with more than one field annotated with `#[id]`:
+///
+/// ```compile_fail
+/// use plotline::id::Identify;
+///
+/// #[derive(Identify)]
+/// struct Character {
+///     #[id]
+///     name: String,
+///     #[id]
+///     surname: String,
+/// }
+/// ```
+///
+/// A `#[property]` annotation on a field that cannot be iterated by reference:
+///
+/// ```compile_fail,E0277
+/// use plotline::property::Property;
+///
+/// struct Age(usize);
+///
+/// impl From<usize> for Age {
+///     fn from(age: usize) -> Self {
+///         Age(age)
+///     }
+/// }
+///
+/// #[derive(Property)]
+/// struct Character {
+///     #[property(Age)]
+///     age: usize,
+/// }
+/// ```
+///
+/// A `#[property]` annotation without a property type:
+///
+/// ```compile_fail
+/// use plotline::property::Property;
+///
+/// #[derive(Property)]
+/// struct Character {
+///     #[property]
+///     friends: Vec<String>,
+/// }
+/// ```
+///
+/// A `#[trigger]` attribute with an argument other than a scheduler:
+///
+/// ```compile_fail
+/// use plotline::{prelude::*, schema::trigger::trigger};
+///
+/// #[derive(Clone, Identify)]
+/// struct Character {
+///     #[id]
+///     name: String,
+/// }
+///
+/// #[trigger("AfterSave")]
+/// fn noop(_: Ctx<Character>) -> Result<()> {
+///     Ok(())
+/// }
+/// ```
+///
+/// A `#[trigger]` attribute without any scheduler:
+///
+/// ```compile_fail
+/// use plotline::{prelude::*, schema::trigger::trigger};
+///
+/// #[derive(Clone, Identify)]
+/// struct Character {
+///     #[id]
+///     name: String,
+/// }
+///
+/// #[trigger()]
+/// fn noop(_: Ctx<Character>) -> Result<()> {
+///     Ok(())
+/// }
+/// ```
+#[cfg(doctest)]
+#[allow(dead_code)]
+struct CompileFail;
@@ -0,0 +1,77 @@
+//! Derive implementation for the `Property` trait.
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{spanned::Spanned, Data, DeriveInput, Error, Fields, Result, Type};
+
+/// Returns the implementations of the `Property` trait for the given input.
+pub fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            input.span(),
+            "Property can only be derived for structs",
+        ));
+    };
+
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new(
+            data.fields.span(),
+            "Property can only be derived for structs with named fields",
+        ));
+    };
+
+    // Groups the annotated fields by property type, keeping the declaration order.
+    let mut properties: Vec<(Type, Vec<&syn::Ident>)> = Vec::new();
+    for field in &fields.named {
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("property"))
+        {
+            let property: Type = attr.parse_args()?;
+            let field_ident = field
+                .ident
+                .as_ref()
+                .expect("named fields should have an identifier");
+
+            let key = property.to_token_stream().to_string();
+            match properties
+                .iter_mut()
+                .find(|(ty, _)| ty.to_token_stream().to_string() == key)
+            {
+                Some((_, fields)) => fields.push(field_ident),
+                None => properties.push((property, vec![field_ident])),
+            }
+        }
+    }
+
+    if properties.is_empty() {
+        return Err(Error::new(
+            input.span(),
+            "missing #[property(...)] annotation in any of the struct fields",
+        ));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    let impls = properties.into_iter().map(|(property, fields)| {
+        quote! {
+            impl #impl_generics ::plotline::property::Property<#name #type_generics> for #property
+            #where_clause
+            {
+                fn all(source: &#name #type_generics) -> ::std::vec::Vec<Self> {
+                    ::std::iter::empty()
+                        #(.chain(::std::iter::IntoIterator::into_iter(&source.#fields)))*
+                        .cloned()
+                        .map(::std::convert::Into::into)
+                        .collect()
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        #(#impls)*
+    })
+}
@@ -0,0 +1,114 @@
+//! Attribute implementation for triggers.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Error, FnArg, GenericArgument, ItemFn, Path, PathArguments, Result, Token, Type,
+};
+
+/// The list of schedulers a trigger has to be scheduled for.
+pub struct Schedulers(Punctuated<Path, Token![,]>);
+
+impl Parse for Schedulers {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let schedulers = Punctuated::parse_terminated(input)?;
+        if schedulers.is_empty() {
+            return Err(input.error("at least one scheduler must be provided"));
+        }
+
+        Ok(Self(schedulers))
+    }
+}
+
+/// Returns the node type from the `Ctx` argument of a trigger.
+fn node_type(input: &ItemFn) -> Result<&Type> {
+    let error = || {
+        Error::new(
+            input.sig.inputs.span(),
+            "the first argument of a trigger must be a Ctx<T>",
+        )
+    };
+
+    let Some(FnArg::Typed(arg)) = input.sig.inputs.first() else {
+        return Err(error());
+    };
+
+    let Type::Path(ctx) = arg.ty.as_ref() else {
+        return Err(error());
+    };
+
+    let Some(segment) = ctx
+        .path
+        .segments
+        .last()
+        .filter(|segment| segment.ident == "Ctx")
+    else {
+        return Err(error());
+    };
+
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return Err(error());
+    };
+
+    args.args
+        .iter()
+        .find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        })
+        .ok_or_else(error)
+}
+
+/// Returns the unit struct and `Plugin` implementation for the given trigger.
+pub fn expand(schedulers: Schedulers, input: ItemFn) -> Result<TokenStream> {
+    if !input.sig.generics.params.is_empty() {
+        return Err(Error::new(
+            input.sig.generics.span(),
+            "generic triggers are not supported",
+        ));
+    }
+
+    let node = node_type(&input)?;
+    let schedulers = schedulers.0.iter();
+
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = &input;
+
+    let name = &sig.ident;
+    let mut inner_sig = sig.clone();
+    inner_sig.ident = syn::Ident::new("trigger", name.span());
+
+    Ok(quote! {
+        #(#attrs)*
+        #[allow(non_camel_case_types)]
+        #vis struct #name;
+
+        impl #name {
+            #vis #inner_sig #block
+        }
+
+        impl ::plotline::schema::plugin::Plugin<#node> for #name {
+            fn name(&self) -> &'static str {
+                ::std::concat!(::std::module_path!(), "::", ::std::stringify!(#name))
+            }
+
+            fn version(&self) -> &'static str {
+                ::std::env!("CARGO_PKG_VERSION")
+            }
+
+            fn install(
+                self,
+                schema: ::plotline::schema::Schema<#node>,
+            ) -> ::plotline::schema::Schema<#node> {
+                schema #(.with_trigger(#schedulers, Self::trigger))*
+            }
+        }
+    })
+}
@@ -0,0 +1,124 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use plotline::{
+    deref::TryDeref,
+    graph::{Graph, NodeProxy, Source},
+    id::Identify,
+    property::Property,
+    schema::{transaction::Transaction, Schema},
+};
+
+/// The size, in bytes, of the content of each node.
+const CONTENT_SIZE: usize = 4096;
+
+/// The amount of nodes visited by each walk, regardless of the size of the graph.
+const STEPS: usize = 1_000;
+
+/// The amount of nodes in each of the benchmarked graphs.
+const SIZES: [usize; 2] = [1_000, 100_000];
+
+/// A node carrying some content, like a document does.
+#[derive(Clone)]
+struct Node {
+    id: usize,
+    edges: Vec<usize>,
+    content: Vec<u8>,
+}
+
+impl Identify for Node {
+    type Id = usize;
+
+    fn id(&self) -> &Self::Id {
+        &self.id
+    }
+}
+
+struct Edge(usize);
+
+impl Identify for Edge {
+    type Id = usize;
+
+    fn id(&self) -> &Self::Id {
+        &self.0
+    }
+}
+
+impl Property<Node> for Edge {
+    fn all(source: &Node) -> Vec<Self> {
+        source.edges.iter().copied().map(Edge).collect()
+    }
+}
+
+/// Returns a graph of the given size in which each node points to the next two ones.
+fn graph(size: usize) -> Graph<Node> {
+    Graph::from_iter((0..size).map(|id| Node {
+        id,
+        edges: vec![(id + 1) % size, (id + 2) % size],
+        content: vec![0; CONTENT_SIZE],
+    }))
+}
+
+/// Follows the first successor of each node, starting at the given one, the given amount of
+/// steps, returning the total amount of content read.
+fn walk<S>(node: NodeProxy<'_, S>, steps: usize) -> usize
+where
+    S: Source<Node = Node>,
+{
+    let mut read = 0;
+    let mut current = node;
+    for _ in 0..steps {
+        read += current
+            .try_deref()
+            .map(|node| node.content.len())
+            .unwrap_or_default();
+        let Some(next) = current.successors::<Edge>().into_iter().next() else {
+            break;
+        };
+
+        current = next;
+    }
+
+    read
+}
+
+fn bench_graph(c: &mut Criterion) {
+    let mut group = c.benchmark_group("graph");
+    for size in SIZES {
+        let graph = graph(size);
+        group.bench_with_input(BenchmarkId::new("walk", size), &graph, |b, graph| {
+            b.iter(|| walk(graph.node(0), STEPS))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_context(c: &mut Criterion) {
+    let mut group = c.benchmark_group("context");
+    for size in SIZES {
+        let schema = Schema::from(graph(size));
+        group.bench_with_input(BenchmarkId::new("walk", size), &schema, |b, schema| {
+            b.iter(|| {
+                schema
+                    .transaction()
+                    .with(|ctx| {
+                        // Overwrite a few nodes so the walk goes through the transaction overlay.
+                        (0..10).for_each(|id| {
+                            ctx.save(Node {
+                                id,
+                                edges: vec![id + 1],
+                                content: vec![1; CONTENT_SIZE],
+                            })
+                        });
+
+                        Ok(walk(ctx.node(0), STEPS))
+                    })
+                    .expect("transaction should not fail")
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_graph, bench_context);
+criterion_main!(benches);
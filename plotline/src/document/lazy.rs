@@ -7,9 +7,42 @@ use std::{
 
 use crate::{deref::TryDeref, id::Identify};
 
-use super::DocumentRepository;
+use super::{DocumentRepository, Revision};
+
+/// The outcome of loading a document from its repository.
+enum State<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    /// The document was loaded at the given revision, if known.
+    Loaded {
+        document: DocumentRepo::Document,
+        revision: Option<Revision>,
+    },
+    /// The document could not be loaded.
+    Failed(Arc<DocumentRepo::Error>),
+}
+
+impl<DocumentRepo> Clone for State<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+    DocumentRepo::Document: Clone,
+{
+    fn clone(&self) -> Self {
+        match self {
+            State::Loaded { document, revision } => State::Loaded {
+                document: document.clone(),
+                revision: *revision,
+            },
+            State::Failed(err) => State::Failed(err.clone()),
+        }
+    }
+}
 
 /// A lazy-loading document from a [`DocumentRepository`].
+///
+/// The document is loaded on first access and cached, whether the load succeeded or not, until
+/// it gets invalidated.
 pub struct LazyDocument<DocumentRepo>
 where
     DocumentRepo: DocumentRepository,
@@ -19,7 +52,7 @@ where
     /// The id of the document being cached.
     document_id: <DocumentRepo::Document as Identify>::Id,
     /// The cached state of the document.
-    document: OnceLock<DocumentRepo::Document>,
+    state: OnceLock<State<DocumentRepo>>,
 }
 
 impl<DocumentRepo> Clone for LazyDocument<DocumentRepo>
@@ -32,7 +65,7 @@ where
         Self {
             document_repo: self.document_repo.clone(),
             document_id: self.document_id.clone(),
-            document: self.document.clone(),
+            state: self.state.clone(),
         }
     }
 }
@@ -56,20 +89,85 @@ where
     type Target = DocumentRepo::Document;
 
     fn try_deref(&self) -> Option<&Self::Target> {
-        if let doc @ Some(_) = self.document.get() {
-            return doc;
+        match self.state.get_or_init(|| self.load()) {
+            State::Loaded { document, .. } => Some(document),
+            State::Failed(_) => None,
         }
+    }
+}
+
+impl<DocumentRepo> LazyDocument<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+    <DocumentRepo::Document as Identify>::Id: Debug,
+{
+    /// Loads the document from the repository.
+    fn load(&self) -> State<DocumentRepo> {
+        // The revision is taken beforehand so a change during the load makes the document stale
+        // instead of going unnoticed.
+        let revision = self.document_repo.revision(&self.document_id);
 
-        if self
-            .document
-            .set(self.document_repo.find_by_id(&self.document_id)?)
-            .is_err()
-        {
-            tracing::error!(id = format!("{:?}", self.document_id), "document not found");
-            return None;
+        match self.document_repo.find_by_id(&self.document_id) {
+            Ok(document) => State::Loaded { document, revision },
+            Err(err) => {
+                tracing::error!(
+                    id = format!("{:?}", self.document_id),
+                    error = format!("{:?}", err),
+                    "loading document"
+                );
+
+                State::Failed(Arc::new(err))
+            }
         }
+    }
+}
 
-        self.document.get()
+impl<DocumentRepo> LazyDocument<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    /// Returns true if, and only if, the document has been loaded successfully.
+    pub fn is_loaded(&self) -> bool {
+        matches!(self.state.get(), Some(State::Loaded { .. }))
+    }
+
+    /// Returns the error of the last load of the document, if it failed.
+    pub fn error(&self) -> Option<&DocumentRepo::Error> {
+        match self.state.get() {
+            Some(State::Failed(err)) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Discards the cached state of the document, so it gets loaded again on next access.
+    pub fn invalidate(&mut self) {
+        self.state.take();
+    }
+
+    /// Returns false if, and only if, the cached state of the document is known to be outdated.
+    ///
+    /// Failed loads are always outdated, while loaded documents are outdated if the repository
+    /// reports a revision other than the loaded one.
+    pub fn is_fresh(&self) -> bool {
+        match self.state.get() {
+            None => true,
+            Some(State::Failed(_)) => false,
+            Some(State::Loaded { revision: None, .. }) => true,
+            Some(State::Loaded {
+                revision: Some(revision),
+                ..
+            }) => self.document_repo.revision(&self.document_id) == Some(*revision),
+        }
+    }
+
+    /// Invalidates the document if its cached state is outdated, returning true if it was.
+    pub fn refresh(&mut self) -> bool {
+        if self.is_fresh() {
+            return false;
+        }
+
+        self.invalidate();
+        true
     }
 }
 
@@ -86,31 +184,142 @@ where
             Self {
                 document_repo: document_repo.clone(),
                 document_id,
-                document: Default::default(),
+                state: Default::default(),
             }
         }
     }
-}
 
-impl<DocumentRepo> LazyDocument<DocumentRepo>
-where
-    DocumentRepo: DocumentRepository,
-    DocumentRepo::Document: Debug,
-    <DocumentRepo::Document as Identify>::Id: Clone,
-{
     /// Returns a [`LazyDocument`] with the given repository and content.
+    ///
+    /// Since the content may differ from the one in the repository, its revision is unknown.
     pub fn new(document_repo: Arc<DocumentRepo>, document: DocumentRepo::Document) -> Self {
-        let lazy_doc = Self {
-            document_repo: document_repo.clone(),
+        Self {
+            document_repo,
             document_id: document.id().clone(),
-            document: Default::default(),
-        };
+            state: OnceLock::from(State::Loaded {
+                document,
+                revision: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        sync::{Arc, Mutex},
+    };
+
+    use crate::{
+        deref::TryDeref,
+        document::{DocumentRepository, Revision},
+        id::Identify,
+    };
+
+    use super::LazyDocument;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Document {
+        id: usize,
+        content: &'static str,
+    }
+
+    impl Identify for Document {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.id
+        }
+    }
+
+    #[derive(Default)]
+    struct DocumentRepositoryMock {
+        documents: Mutex<BTreeMap<usize, &'static str>>,
+    }
+
+    impl DocumentRepository for DocumentRepositoryMock {
+        type Document = Document;
+        type Error = &'static str;
+
+        fn find_by_id(&self, id: &usize) -> Result<Self::Document, Self::Error> {
+            let documents = self.documents.lock().unwrap();
+            documents
+                .get(id)
+                .map(|content| Document { id: *id, content })
+                .ok_or("not found")
+        }
+
+        fn revision(&self, id: &usize) -> Option<Revision> {
+            let documents = self.documents.lock().unwrap();
+            documents
+                .get(id)
+                .map(|content| Revision::hash(content.as_bytes()))
+        }
+    }
+
+    impl DocumentRepositoryMock {
+        fn with_document(self, id: usize, content: &'static str) -> Self {
+            self.documents.lock().unwrap().insert(id, content);
+            self
+        }
+    }
+
+    fn content(document: &LazyDocument<DocumentRepositoryMock>) -> Option<&'static str> {
+        document.try_deref().map(|document| document.content)
+    }
+
+    #[test]
+    fn document_should_be_cached_until_invalidated() {
+        let repo = Arc::new(DocumentRepositoryMock::default().with_document(1, "v1"));
+        let mut document = LazyDocument::builder(repo.clone())(1);
+
+        assert!(!document.is_loaded(), "document should load lazily");
+        assert_eq!(content(&document), Some("v1"));
+
+        repo.documents.lock().unwrap().insert(1, "v2");
+        assert_eq!(content(&document), Some("v1"), "document should be cached");
+
+        document.invalidate();
+        assert_eq!(
+            content(&document),
+            Some("v2"),
+            "document should be reloaded"
+        );
+    }
+
+    #[test]
+    fn stale_documents_should_be_refreshed() {
+        let repo = Arc::new(DocumentRepositoryMock::default().with_document(1, "v1"));
+        let mut document = LazyDocument::builder(repo.clone())(1);
+
+        assert_eq!(content(&document), Some("v1"));
+        assert!(document.is_fresh());
+        assert!(
+            !document.refresh(),
+            "fresh documents should not be refreshed"
+        );
+
+        repo.documents.lock().unwrap().insert(1, "v2");
+        assert!(!document.is_fresh(), "changed documents should be stale");
+        assert!(document.refresh(), "stale documents should be refreshed");
+        assert_eq!(content(&document), Some("v2"));
+    }
+
+    #[test]
+    fn failed_loads_should_be_inspectable_and_retried() {
+        let repo = Arc::new(DocumentRepositoryMock::default());
+        let mut document = LazyDocument::builder(repo.clone())(1);
+
+        assert_eq!(content(&document), None);
+        assert_eq!(document.error(), Some(&"not found"));
+        assert!(!document.is_fresh(), "failed loads should be stale");
 
-        lazy_doc
-            .document
-            .set(document)
-            .expect("default once-lock should be uninitialized");
+        repo.documents.lock().unwrap().insert(1, "v1");
+        assert_eq!(content(&document), None, "failed loads should be cached");
 
-        lazy_doc
+        assert!(document.refresh());
+        assert_eq!(content(&document), Some("v1"));
+        assert_eq!(document.error(), None);
     }
 }
@@ -1,14 +1,51 @@
 //! Document related definitions.
 
+use std::{
+    fmt::Debug,
+    hash::{DefaultHasher, Hash, Hasher},
+    time::SystemTime,
+};
+
 use crate::id::Identify;
 
 pub mod lazy;
 
+/// Identifies a specific version of the content of a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Revision {
+    /// The last modification time and size of the document.
+    Modified(SystemTime, u64),
+    /// A hash of the content of the document.
+    Hash(u64),
+}
+
+impl Revision {
+    /// Returns the revision identified by the hash of the given content.
+    pub fn hash(content: &[u8]) -> Self {
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+        Revision::Hash(hasher.finish())
+    }
+}
+
 /// A repository in charge of document's persistance.
 pub trait DocumentRepository {
     /// The type of document retrived by the repository.
     type Document: Identify;
+    /// The type of error returned when a document cannot be retrived.
+    type Error: Debug;
+
+    /// Retrives the document with the given id.
+    fn find_by_id(
+        &self,
+        id: &<Self::Document as Identify>::Id,
+    ) -> Result<Self::Document, Self::Error>;
 
-    /// Retrives the document with the given id, if any.
-    fn find_by_id(&self, id: &<Self::Document as Identify>::Id) -> Option<Self::Document>;
+    /// Returns the current revision of the document with the given id, if known.
+    ///
+    /// Repositories able to tell cheaply when a document has changed should implement this
+    /// method, so cached documents can be checked for freshness.
+    fn revision(&self, _id: &<Self::Document as Identify>::Id) -> Option<Revision> {
+        None
+    }
 }
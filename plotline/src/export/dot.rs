@@ -0,0 +1,49 @@
+//! Export into the Graphviz DOT language.
+
+use std::io::{self, Write};
+
+use super::Subgraph;
+
+/// Returns the given text as a quoted DOT string.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+pub(super) fn write<Id, W: Write>(subgraph: &Subgraph<Id>, writer: &mut W) -> io::Result<()> {
+    let write_node = |writer: &mut W, index: usize, indent: &str| {
+        let node = &subgraph.nodes[index];
+        let style = if node.is_virtual {
+            ", style=dashed"
+        } else {
+            ""
+        };
+        writeln!(
+            writer,
+            "{indent}n{index} [label={}{style}];",
+            quote(&node.label)
+        )
+    };
+
+    writeln!(writer, "digraph {{")?;
+
+    let (clusters, unclustered) = subgraph.clusters();
+    for (cluster, (name, indexes)) in clusters.into_iter().enumerate() {
+        writeln!(writer, "    subgraph cluster_{cluster} {{")?;
+        writeln!(writer, "        label={};", quote(name))?;
+        for index in indexes {
+            write_node(writer, index, "        ")?;
+        }
+
+        writeln!(writer, "    }}")?;
+    }
+
+    for index in unclustered {
+        write_node(writer, index, "    ")?;
+    }
+
+    for (source, target) in &subgraph.edges {
+        writeln!(writer, "    n{source} -> n{target};")?;
+    }
+
+    writeln!(writer, "}}")
+}
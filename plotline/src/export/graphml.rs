@@ -0,0 +1,66 @@
+//! Export into the GraphML format.
+
+use std::io::{self, Write};
+
+use super::Subgraph;
+
+/// Returns the given text with the XML special characters escaped.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+pub(super) fn write<Id, W: Write>(subgraph: &Subgraph<Id>, writer: &mut W) -> io::Result<()> {
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    for (key, kind) in [
+        ("label", "string"),
+        ("cluster", "string"),
+        ("virtual", "boolean"),
+    ] {
+        writeln!(
+            writer,
+            r#"  <key id="{key}" for="node" attr.name="{key}" attr.type="{kind}"/>"#
+        )?;
+    }
+
+    writeln!(writer, r#"  <graph id="G" edgedefault="directed">"#)?;
+    for (index, node) in subgraph.nodes.iter().enumerate() {
+        writeln!(writer, r#"    <node id="n{index}">"#)?;
+        writeln!(
+            writer,
+            r#"      <data key="label">{}</data>"#,
+            escape(&node.label)
+        )?;
+        if let Some(cluster) = &node.cluster {
+            writeln!(
+                writer,
+                r#"      <data key="cluster">{}</data>"#,
+                escape(cluster)
+            )?;
+        }
+
+        writeln!(
+            writer,
+            r#"      <data key="virtual">{}</data>"#,
+            node.is_virtual
+        )?;
+        writeln!(writer, "    </node>")?;
+    }
+
+    for (source, target) in &subgraph.edges {
+        writeln!(
+            writer,
+            r#"    <edge source="n{source}" target="n{target}"/>"#
+        )?;
+    }
+
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</graphml>")
+}
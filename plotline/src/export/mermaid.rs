@@ -0,0 +1,45 @@
+//! Export into a Mermaid flowchart.
+
+use std::io::{self, Write};
+
+use super::Subgraph;
+
+/// Returns the given text as a quoted Mermaid string, using entity codes for the characters
+/// that would break it.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "#quot;").replace('\n', " "))
+}
+
+pub(super) fn write<Id, W: Write>(subgraph: &Subgraph<Id>, writer: &mut W) -> io::Result<()> {
+    let write_node = |writer: &mut W, index: usize, indent: &str| {
+        let node = &subgraph.nodes[index];
+        let class = if node.is_virtual { ":::virtual" } else { "" };
+        writeln!(writer, "{indent}n{index}[{}]{class}", quote(&node.label))
+    };
+
+    writeln!(writer, "flowchart LR")?;
+
+    let (clusters, unclustered) = subgraph.clusters();
+    for (cluster, (name, indexes)) in clusters.into_iter().enumerate() {
+        writeln!(writer, "    subgraph c{cluster}[{}]", quote(name))?;
+        for index in indexes {
+            write_node(writer, index, "        ")?;
+        }
+
+        writeln!(writer, "    end")?;
+    }
+
+    for index in unclustered {
+        write_node(writer, index, "    ")?;
+    }
+
+    for (source, target) in &subgraph.edges {
+        writeln!(writer, "    n{source} --> n{target}")?;
+    }
+
+    if subgraph.nodes.iter().any(|node| node.is_virtual) {
+        writeln!(writer, "    classDef virtual stroke-dasharray: 5 5")?;
+    }
+
+    Ok(())
+}
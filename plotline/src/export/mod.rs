@@ -0,0 +1,372 @@
+//! Exporters of graphs into textual formats.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    fmt::Debug,
+    io::{self, Write},
+    marker::PhantomData,
+};
+
+use crate::{deref::TryDeref, graph::Graph, id::Identify, property::Property};
+
+mod dot;
+mod graphml;
+mod mermaid;
+
+/// The formats a graph can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// The Graphviz DOT language.
+    Dot,
+    /// A Mermaid flowchart.
+    Mermaid,
+    /// The GraphML XML format.
+    GraphMl,
+}
+
+/// Computes the label, or cluster, of a node given its id and, unless virtual, its value.
+type NodeFn<'a, T, Output> = Box<dyn Fn(&<T as Identify>::Id, Option<&T>) -> Output + 'a>;
+
+/// A node to be exported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportedNode<Id> {
+    pub id: Id,
+    pub label: String,
+    pub cluster: Option<String>,
+    /// Whether the node is pointed by an edge but missing in the graph.
+    pub is_virtual: bool,
+}
+
+/// The portion of a graph to be exported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subgraph<Id> {
+    /// The exported nodes, in order of id.
+    pub nodes: Vec<ExportedNode<Id>>,
+    /// The edges between the exported nodes, as pairs of indexes into the nodes.
+    pub edges: BTreeSet<(usize, usize)>,
+}
+
+impl<Id> Subgraph<Id> {
+    /// Returns the indexes of the nodes in each cluster, by cluster name, together with those
+    /// not belonging to any.
+    fn clusters(&self) -> (BTreeMap<&str, Vec<usize>>, Vec<usize>) {
+        let mut clusters = BTreeMap::<_, Vec<_>>::new();
+        let mut unclustered = Vec::new();
+        self.nodes
+            .iter()
+            .enumerate()
+            .for_each(|(index, node)| match &node.cluster {
+                Some(cluster) => clusters.entry(cluster.as_str()).or_default().push(index),
+                None => unclustered.push(index),
+            });
+
+        (clusters, unclustered)
+    }
+}
+
+/// Exports the nodes of a [`Graph`] and the edges between them, as given by the property Edge.
+///
+/// Nodes pointed by an edge but not in the graph are exported as virtual.
+pub struct Exporter<'a, T, Edge>
+where
+    T: Identify,
+{
+    graph: &'a Graph<T>,
+    /// The nodes to start walking from, or all of them if empty.
+    roots: Vec<T::Id>,
+    /// The maximum distance from the roots of the exported nodes, if any.
+    depth: Option<usize>,
+    label: NodeFn<'a, T, String>,
+    cluster: NodeFn<'a, T, Option<String>>,
+    _edge: PhantomData<fn() -> Edge>,
+}
+
+impl<'a, T, Edge> Exporter<'a, T, Edge>
+where
+    T: Identify + Clone,
+    T::Id: Ord + Clone + Debug,
+    Edge: Property<T> + Identify<Id = T::Id>,
+{
+    /// Returns an exporter of the whole graph, labeling each node with its id.
+    pub fn new(graph: &'a Graph<T>) -> Self {
+        Self {
+            graph,
+            roots: Vec::default(),
+            depth: None,
+            label: Box::new(|id, _| format!("{id:?}")),
+            cluster: Box::new(|_, _| None),
+            _edge: PhantomData,
+        }
+    }
+
+    /// Exports only the nodes reachable from the given one, along with those of any other
+    /// root.
+    pub fn with_root(mut self, root: T::Id) -> Self {
+        self.roots.push(root);
+        self
+    }
+
+    /// Exports only the nodes at most at the given distance from the roots.
+    pub fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    /// Labels each node with the output of the given closure.
+    pub fn with_label<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(&T::Id, Option<&T>) -> String,
+    {
+        self.label = Box::new(f);
+        self
+    }
+
+    /// Groups the nodes by the cluster returned by the given closure, if any.
+    pub fn with_cluster<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(&T::Id, Option<&T>) -> Option<String>,
+    {
+        self.cluster = Box::new(f);
+        self
+    }
+
+    /// Writes the graph into the given writer in the given format.
+    pub fn export<W: Write>(&self, format: Format, writer: &mut W) -> io::Result<()> {
+        let subgraph = self.subgraph();
+        match format {
+            Format::Dot => dot::write(&subgraph, writer),
+            Format::Mermaid => mermaid::write(&subgraph, writer),
+            Format::GraphMl => graphml::write(&subgraph, writer),
+        }
+    }
+
+    /// Walks the graph from the roots, in breadth-first order, up to the maximum depth.
+    pub fn subgraph(&self) -> Subgraph<T::Id> {
+        let mut queue: VecDeque<_> = if self.roots.is_empty() {
+            self.graph
+                .into_iter()
+                .map(|node| (node.id().clone(), 0))
+                .collect()
+        } else {
+            self.roots.iter().map(|id| (id.clone(), 0)).collect()
+        };
+
+        let mut ids: BTreeSet<_> = queue.iter().map(|(id, _)| id.clone()).collect();
+        while let Some((id, depth)) = queue.pop_front() {
+            if self.depth.is_some_and(|max| depth >= max) {
+                continue;
+            }
+
+            self.graph
+                .node(id)
+                .successors::<Edge>()
+                .into_iter()
+                .for_each(|successor| {
+                    if ids.insert(successor.id.clone()) {
+                        queue.push_back((successor.id, depth + 1));
+                    }
+                });
+        }
+
+        let indexes: BTreeMap<_, _> = ids
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (id, index))
+            .collect();
+
+        let mut subgraph = Subgraph {
+            nodes: Vec::with_capacity(ids.len()),
+            edges: BTreeSet::new(),
+        };
+
+        ids.iter().enumerate().for_each(|(index, id)| {
+            let proxy = self.graph.node(id.clone());
+            let node = proxy.try_deref();
+
+            subgraph.nodes.push(ExportedNode {
+                id: id.clone(),
+                label: (self.label)(id, node),
+                cluster: (self.cluster)(id, node),
+                is_virtual: node.is_none(),
+            });
+
+            proxy
+                .successors::<Edge>()
+                .iter()
+                .filter_map(|successor| indexes.get(&successor.id))
+                .for_each(|successor| {
+                    subgraph.edges.insert((index, *successor));
+                });
+        });
+
+        subgraph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{graph::Graph, id::Identify, property::Property};
+
+    use super::{Exporter, Format};
+
+    #[derive(Debug, Clone)]
+    struct Node {
+        id: usize,
+        edges: Vec<usize>,
+    }
+
+    impl Identify for Node {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.id
+        }
+    }
+
+    struct Edge(usize);
+
+    impl Identify for Edge {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.0
+        }
+    }
+
+    impl Property<Node> for Edge {
+        fn all(source: &Node) -> Vec<Self> {
+            source.edges.iter().copied().map(Edge).collect()
+        }
+    }
+
+    /// A chain of nodes where 3 points to the missing node 4, and 5 is isolated.
+    fn graph() -> Graph<Node> {
+        Graph::from_iter([
+            Node {
+                id: 1,
+                edges: vec![2, 2],
+            },
+            Node {
+                id: 2,
+                edges: vec![3, 1],
+            },
+            Node {
+                id: 3,
+                edges: vec![4],
+            },
+            Node {
+                id: 5,
+                edges: vec![],
+            },
+        ])
+    }
+
+    fn export(exporter: Exporter<'_, Node, Edge>, format: Format) -> String {
+        let mut output = Vec::new();
+        exporter
+            .export(format, &mut output)
+            .expect("export should not fail");
+
+        String::from_utf8(output).expect("output should be utf-8")
+    }
+
+    #[test]
+    fn whole_graph_should_be_exported() {
+        let graph = graph();
+
+        assert_eq!(
+            export(Exporter::new(&graph), Format::Dot),
+            concat!(
+                "digraph {\n",
+                "    n0 [label=\"1\"];\n",
+                "    n1 [label=\"2\"];\n",
+                "    n2 [label=\"3\"];\n",
+                "    n3 [label=\"4\", style=dashed];\n",
+                "    n4 [label=\"5\"];\n",
+                "    n0 -> n1;\n",
+                "    n1 -> n0;\n",
+                "    n1 -> n2;\n",
+                "    n2 -> n3;\n",
+                "}\n",
+            )
+        );
+    }
+
+    #[test]
+    fn walk_should_stop_at_depth() {
+        let graph = graph();
+        let exporter = Exporter::new(&graph)
+            .with_root(1)
+            .with_depth(1)
+            .with_label(|id, _| format!("node \"{id}\""));
+
+        assert_eq!(
+            export(exporter, Format::Mermaid),
+            concat!(
+                "flowchart LR\n",
+                "    n0[\"node #quot;1#quot;\"]\n",
+                "    n1[\"node #quot;2#quot;\"]\n",
+                "    n0 --> n1\n",
+                "    n1 --> n0\n",
+            )
+        );
+    }
+
+    #[test]
+    fn nodes_should_be_clustered() {
+        let graph = graph();
+        let exporter = Exporter::new(&graph)
+            .with_root(2)
+            .with_cluster(|id, _| (*id < 3).then(|| "low".to_string()));
+
+        assert_eq!(
+            export(exporter, Format::Dot),
+            concat!(
+                "digraph {\n",
+                "    subgraph cluster_0 {\n",
+                "        label=\"low\";\n",
+                "        n0 [label=\"1\"];\n",
+                "        n1 [label=\"2\"];\n",
+                "    }\n",
+                "    n2 [label=\"3\"];\n",
+                "    n3 [label=\"4\", style=dashed];\n",
+                "    n0 -> n1;\n",
+                "    n1 -> n0;\n",
+                "    n1 -> n2;\n",
+                "    n2 -> n3;\n",
+                "}\n",
+            )
+        );
+
+        assert_eq!(
+            export(
+                Exporter::new(&graph)
+                    .with_root(3)
+                    .with_label(|id, _| format!("<{id}>"))
+                    .with_cluster(|id, _| Some(format!("{}", id % 2))),
+                Format::GraphMl
+            ),
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+                "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+                "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n",
+                "  <key id=\"cluster\" for=\"node\" attr.name=\"cluster\" attr.type=\"string\"/>\n",
+                "  <key id=\"virtual\" for=\"node\" attr.name=\"virtual\" attr.type=\"boolean\"/>\n",
+                "  <graph id=\"G\" edgedefault=\"directed\">\n",
+                "    <node id=\"n0\">\n",
+                "      <data key=\"label\">&lt;3&gt;</data>\n",
+                "      <data key=\"cluster\">1</data>\n",
+                "      <data key=\"virtual\">false</data>\n",
+                "    </node>\n",
+                "    <node id=\"n1\">\n",
+                "      <data key=\"label\">&lt;4&gt;</data>\n",
+                "      <data key=\"cluster\">0</data>\n",
+                "      <data key=\"virtual\">true</data>\n",
+                "    </node>\n",
+                "    <edge source=\"n0\" target=\"n1\"/>\n",
+                "  </graph>\n",
+                "</graphml>\n",
+            )
+        );
+    }
+}
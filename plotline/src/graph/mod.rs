@@ -1,6 +1,9 @@
 //! Graph related definitions.
 
-use std::collections::{btree_map::Values, BTreeMap};
+use std::{borrow::Cow, fmt::Debug};
+
+use archery::ArcK;
+use rpds::{map::red_black_tree_map::IterValues, RedBlackTreeMapSync};
 
 use crate::id::Identify;
 
@@ -8,19 +11,49 @@ mod proxy;
 pub use proxy::*;
 
 /// An arbitrary graph.
-#[derive(Debug)]
+///
+/// Nodes are stored in a persistent map, so cloning a graph is cheap: the clone shares its
+/// structure with the original one, and any later change in either of them leaves the other
+/// untouched.
 pub struct Graph<T>
 where
     T: Identify,
 {
     /// All the nodes in the graph.
-    nodes: BTreeMap<T::Id, T>,
+    nodes: RedBlackTreeMapSync<T::Id, T>,
+}
+
+impl<T> Debug for Graph<T>
+where
+    T: Identify + Debug,
+    T::Id: Ord + Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Graph").field("nodes", &self.nodes).finish()
+    }
 }
 
-impl<T: Identify> Default for Graph<T> {
+impl<T> Default for Graph<T>
+where
+    T: Identify,
+    T::Id: Ord,
+{
     fn default() -> Self {
         Self {
-            nodes: Default::default(),
+            nodes: RedBlackTreeMapSync::new_sync(),
+        }
+    }
+}
+
+impl<T> Clone for Graph<T>
+where
+    T: Identify,
+    T::Id: Ord,
+{
+    /// Returns a snapshot of the graph sharing its structure with self.
+    fn clone(&self) -> Self {
+        Self {
+            nodes: self.nodes.clone(),
         }
     }
 }
@@ -36,18 +69,50 @@ where
     /// only the latest node will remain.
     fn from_iter<V: IntoIterator<Item = T>>(nodes: V) -> Self {
         Self {
-            nodes: BTreeMap::from_iter(nodes.into_iter().map(|node| (node.id().clone(), node))),
+            nodes: RedBlackTreeMapSync::from_iter(
+                nodes.into_iter().map(|node| (node.id().clone(), node)),
+            ),
         }
     }
 }
 
+/// Graphs are serialized as the sequence of their nodes.
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for Graph<T>
+where
+    T: Identify + serde::Serialize,
+    T::Id: Ord,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for Graph<T>
+where
+    T: Identify + serde::Deserialize<'de>,
+    T::Id: Ord + Clone,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Vec::<T>::deserialize(deserializer).map(Self::from_iter)
+    }
+}
+
 impl<'a, T> IntoIterator for &'a Graph<T>
 where
     T: Identify,
+    T::Id: Ord,
 {
     type Item = &'a T;
 
-    type IntoIter = Values<'a, T::Id, T>;
+    type IntoIter = IterValues<'a, T::Id, T, ArcK>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.nodes.values()
@@ -61,8 +126,8 @@ where
 {
     type Node = T;
 
-    fn get(&self, id: &<Self::Node as Identify>::Id) -> Option<Self::Node> {
-        self.nodes.get(id).cloned()
+    fn get(&self, id: &<Self::Node as Identify>::Id) -> Option<Cow<'_, Self::Node>> {
+        self.nodes.get(id).map(Cow::Borrowed)
     }
 
     fn contains(&self, id: &<Self::Node as Identify>::Id) -> bool {
@@ -77,13 +142,38 @@ where
 {
     /// Inserts the given node into the graph, overwriting any previous value with the same id.
     pub fn with_node(mut self, node: T) -> Self {
-        self.nodes.insert(node.id().clone(), node);
+        self.store(node);
         self
     }
 
+    /// Inserts the given node into the graph, dropping any previous value with the same id.
+    pub(crate) fn store(&mut self, node: T) {
+        self.nodes.insert_mut(node.id().clone(), node);
+    }
+}
+
+impl<T> Graph<T>
+where
+    T: Identify + Clone,
+    T::Id: Ord + Clone,
+{
     /// Inserts the given node into the graph, returning the previous node with that same id, if any.
+    ///
+    /// The previous node is cloned out of the graph, since it may be shared with any snapshot.
     pub fn insert(&mut self, node: T) -> Option<T> {
-        self.nodes.insert(node.id().clone(), node)
+        let id = node.id().clone();
+        let previous = self.nodes.get(&id).cloned();
+        self.nodes.insert_mut(id, node);
+        previous
+    }
+
+    /// Removes the node with the given id from the graph, returning it, if any.
+    ///
+    /// The node is cloned out of the graph, since it may be shared with any snapshot.
+    pub fn remove(&mut self, node_id: &T::Id) -> Option<T> {
+        let node = self.nodes.get(node_id).cloned();
+        self.nodes.remove_mut(node_id);
+        node
     }
 }
 
@@ -103,9 +193,19 @@ where
     T: Identify,
     T::Id: Ord,
 {
-    /// Removes the node with the given id from the graph, returning it, if any.
-    pub fn remove(&mut self, node_id: &T::Id) -> Option<T> {
-        self.nodes.remove(node_id)
+    /// Removes the node with the given id from the graph, dropping it.
+    pub(crate) fn discard(&mut self, node_id: &T::Id) {
+        self.nodes.remove_mut(node_id);
+    }
+
+    /// Returns the amount of nodes in the graph.
+    pub fn len(&self) -> usize {
+        self.nodes.size()
+    }
+
+    /// Returns true if, and only if, the graph has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
     }
 }
 
@@ -176,3 +276,34 @@ pub mod fixtures {
     #[allow(unused_imports)]
     pub(crate) use fake_node;
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::id::Identify;
+
+    use super::{
+        fixtures::{fake_node, FakeNode},
+        Graph,
+    };
+
+    #[test]
+    fn previous_nodes_should_be_returned_and_kept_in_snapshots() {
+        let mut graph = Graph::default().with_node(fake_node!(1));
+        let snapshot = graph.clone();
+
+        assert!(graph.insert(fake_node!(2)).is_none());
+        assert_eq!(graph.insert(fake_node!(1)).map(|node| *node.id()), Some(1));
+        assert_eq!(graph.remove(&1).map(|node| *node.id()), Some(1));
+        assert!(graph.remove(&1).is_none());
+
+        assert_eq!(graph.len(), 1);
+        assert_eq!(
+            snapshot
+                .into_iter()
+                .map(|node| *node.id())
+                .collect::<Vec<_>>(),
+            vec![1],
+            "snapshots should not be changed"
+        );
+    }
+}
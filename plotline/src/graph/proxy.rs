@@ -1,6 +1,6 @@
 //! A proxy for nodes in a graph.
 
-use std::sync::OnceLock;
+use std::{borrow::Cow, sync::OnceLock};
 
 use crate::{deref::TryDeref, id::Identify, property::Property};
 
@@ -8,14 +8,13 @@ use crate::{deref::TryDeref, id::Identify, property::Property};
 ///
 /// This trait allows [`NodeProxy`] to be graph-agnostic.
 pub trait Source {
-    type Node: Identify;
+    type Node: Identify + Clone;
 
     /// Provides the node with the given id, if any.
-    //
-    // TODO: try to return Option<&Self::Node>.
-    //       This method was previously returning a reference, but the transaction
-    //       feature required this to become a value.
-    fn get(&self, id: &<Self::Node as Identify>::Id) -> Option<Self::Node>;
+    ///
+    /// The node is borrowed from the source whenever possible. Only those sources that cannot
+    /// hand out references to their nodes, like transaction overlays, return an owned value.
+    fn get(&self, id: &<Self::Node as Identify>::Id) -> Option<Cow<'_, Self::Node>>;
     /// Returns true if, and only if, a node with the given id exist in the source.
     /// Otherwise returns false.
     fn contains(&self, id: &<Self::Node as Identify>::Id) -> bool;
@@ -32,13 +31,12 @@ where
     /// The id of the node.
     pub id: <S::Node as Identify>::Id,
     /// The actual node.
-    value: OnceLock<Option<S::Node>>,
+    value: OnceLock<Option<Cow<'a, S::Node>>>,
 }
 
 impl<S> Clone for NodeProxy<'_, S>
 where
     S: Source,
-    S::Node: Identify + Clone,
     <S::Node as Identify>::Id: Clone,
 {
     fn clone(&self) -> Self {
@@ -73,7 +71,7 @@ where
     fn try_deref(&self) -> Option<&Self::Target> {
         self.value
             .get_or_init(|| self.source.get(&self.id))
-            .as_ref()
+            .as_deref()
     }
 }
 
@@ -125,9 +123,14 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::graph::{
-        fixtures::{fake_node, FakeEdge, FakeNode},
-        Graph,
+    use std::borrow::Cow;
+
+    use crate::{
+        graph::{
+            fixtures::{fake_node, FakeEdge, FakeNode},
+            Graph, Source,
+        },
+        schema::{transaction::Transaction, Schema},
     };
 
     #[test]
@@ -164,4 +167,41 @@ mod tests {
         assert_eq!(edges_2.len(), 1);
         assert_eq!(edges_2[0].id, 1);
     }
+
+    #[test]
+    fn graph_nodes_should_be_borrowed() {
+        let graph = Graph::default().with_node(fake_node!(1));
+
+        assert!(
+            matches!(graph.get(&1), Some(Cow::Borrowed(_))),
+            "nodes from a graph should not be cloned"
+        );
+    }
+
+    #[test]
+    fn context_nodes_should_be_borrowed_unless_overwritten() {
+        let schema: Schema<_> = Graph::from_iter([fake_node!(1), fake_node!(2)]).into();
+
+        schema
+            .transaction()
+            .with(|ctx| {
+                ctx.save(fake_node!(2, 1));
+
+                assert!(
+                    matches!(ctx.get(&1), Some(Cow::Borrowed(_))),
+                    "untouched nodes should be borrowed from the graph"
+                );
+
+                assert!(
+                    matches!(ctx.get(&2), Some(Cow::Owned(_))),
+                    "overwritten nodes should come from the transaction"
+                );
+
+                let edges = ctx.node(2).successors::<FakeEdge<i8>>();
+                assert_eq!(edges.len(), 1, "overwritten node should be traversable");
+
+                Ok(())
+            })
+            .expect("transaction should not fail");
+    }
 }
@@ -1,5 +1,8 @@
 //! Identity definition.
 
+#[cfg(feature = "macros")]
+pub use plotline_macros::Identify;
+
 /// An entity that can be uniquely identified.
 pub trait Identify {
     type Id;
@@ -24,3 +27,31 @@ pub mod fixtures {
         }
     }
 }
+
+#[cfg(all(test, feature = "macros"))]
+mod tests {
+    use super::Identify;
+
+    #[test]
+    fn derive_identify_with_annotated_field() {
+        #[derive(Identify)]
+        struct Character {
+            #[id]
+            name: &'static str,
+        }
+
+        let character = Character { name: "Alice" };
+        assert_eq!(character.id(), &"Alice");
+    }
+
+    #[test]
+    fn derive_identify_with_id_field() {
+        #[derive(Identify)]
+        struct Character<Id> {
+            id: Id,
+        }
+
+        let character = Character { id: 1 };
+        assert_eq!(character.id(), &1);
+    }
+}
@@ -1,7 +1,9 @@
 pub mod deref;
 pub mod document;
+pub mod export;
 pub mod graph;
 pub mod id;
+pub mod prefix;
 pub mod prelude;
 pub mod property;
 pub mod schema;
@@ -0,0 +1,48 @@
+//! Prefix definition.
+
+use std::path::{Path, PathBuf};
+
+/// A value that may be prefixed by another one.
+pub trait Prefixed {
+    /// Returns true if, and only if, self starts with the given prefix.
+    fn starts_with(&self, prefix: &Self) -> bool;
+}
+
+impl Prefixed for String {
+    fn starts_with(&self, prefix: &Self) -> bool {
+        self.as_str().starts_with(prefix.as_str())
+    }
+}
+
+impl Prefixed for PathBuf {
+    fn starts_with(&self, prefix: &Self) -> bool {
+        Path::starts_with(self, prefix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::Prefixed;
+
+    #[test]
+    fn prefixed_values() {
+        let prefixed = |id: &str, prefix: &str| {
+            Prefixed::starts_with(&String::from(id), &String::from(prefix))
+        };
+
+        assert!(prefixed("characters/alice", "characters/"));
+        assert!(!prefixed("places/home", "characters/"));
+
+        let prefixed = |id: &str, prefix: &str| {
+            Prefixed::starts_with(&PathBuf::from(id), &PathBuf::from(prefix))
+        };
+
+        assert!(prefixed("characters/alice", "characters"));
+        assert!(
+            !prefixed("characters_old/alice", "characters"),
+            "path prefixes should match whole components"
+        );
+    }
+}
@@ -8,8 +8,9 @@ pub use crate::schema::{
         delete::{AfterDelete, BeforeDelete},
         save::{AfterSave, BeforeSave},
     },
-    plugin::Plugin,
+    plugin::{OnInstall, Plugin},
     resource::Res,
+    rule::{Rule, Severity, Violation},
     transaction::{Ctx, Target, Transaction},
     Error, Result, Schema,
 };
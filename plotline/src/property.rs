@@ -1,7 +1,64 @@
 //! Property definition.
 
+#[cfg(feature = "macros")]
+pub use plotline_macros::Property;
+
 /// A value in a source.
 pub trait Property<Src>: Sized {
     /// Retrives all the ocurrences of self in the source.
     fn all(source: &Src) -> Vec<Self>;
 }
+
+#[cfg(all(test, feature = "macros"))]
+mod tests {
+    use super::Property;
+
+    #[derive(Debug, PartialEq)]
+    struct Friend(&'static str);
+
+    impl From<&'static str> for Friend {
+        fn from(name: &'static str) -> Self {
+            Friend(name)
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Age(usize);
+
+    impl From<usize> for Age {
+        fn from(age: usize) -> Self {
+            Age(age)
+        }
+    }
+
+    #[derive(Property)]
+    struct Character {
+        #[property(Friend)]
+        friends: Vec<&'static str>,
+        #[property(Friend)]
+        best_friend: Option<&'static str>,
+        #[property(Age)]
+        age: Option<usize>,
+    }
+
+    #[test]
+    fn derive_property_from_annotated_fields() {
+        let character = Character {
+            friends: vec!["Bob", "Charlie"],
+            best_friend: Some("Dave"),
+            age: None,
+        };
+
+        assert_eq!(
+            Friend::all(&character),
+            vec![Friend("Bob"), Friend("Charlie"), Friend("Dave")],
+            "all fields annotated with the same property should be chained"
+        );
+
+        assert_eq!(
+            Age::all(&character),
+            Vec::default(),
+            "empty fields should produce no properties"
+        );
+    }
+}
@@ -9,6 +9,15 @@ pub enum Error {
     /// Determines that an operation has no effect.
     #[error("nothing to apply")]
     Noop,
+    /// A plugin with the same name is already installed.
+    #[error("plugin {0} is already installed")]
+    DuplicatedPlugin(&'static str),
+    /// A plugin depends on another one that is not installed.
+    #[error("plugin {plugin} depends on {dependency}, which is not installed")]
+    MissingDependency {
+        plugin: &'static str,
+        dependency: &'static str,
+    },
     #[error("{0}")]
     Msg(String),
 }
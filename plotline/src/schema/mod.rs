@@ -6,20 +6,27 @@ pub mod guard;
 pub mod ops;
 pub mod plugin;
 pub mod resource;
+pub mod rule;
+pub mod subscription;
 pub mod transaction;
 pub mod trigger;
 
 use std::sync::RwLock;
 
 use guard::{SchemaReadGuard, SchemaWriteGuard};
-use plugin::Plugin;
-use resource::ResourceSet;
-use transaction::Background;
+use plugin::{OnInstall, Plugin, PluginMeta, PluginSet};
+use resource::{InitializerSet, Res, ResourceSet};
+use rule::{Rule, RuleSet};
+use subscription::{Filter, SubscriberSet, Subscription};
+use transaction::{Background, Transaction};
 use trigger::{Trigger, TriggerSet};
 
 use crate::{graph::Graph, id::Identify};
 
 /// A graph that is subject to a set of rules.
+///
+/// Resources and triggers are required to be thread-safe, so the schema is [`Send`] and
+/// [`Sync`] as long as its nodes are.
 pub struct Schema<T>
 where
     T: Identify,
@@ -28,8 +35,16 @@ where
     graph: RwLock<Graph<T>>,
     /// All the resources in this schema.
     resources: ResourceSet,
+    /// All the initializers of lazy resources in this schema.
+    initializers: InitializerSet<T>,
     /// All the triggers in the schema.
     triggers: TriggerSet<T>,
+    /// All the plugins installed in the schema.
+    plugins: PluginSet,
+    /// All the rules the graph is expected to follow.
+    rules: RuleSet<T>,
+    /// All the subscriptions to the schema's changes.
+    subscribers: SubscriberSet<T>,
 }
 
 impl<T> From<Graph<T>> for Schema<T>
@@ -40,39 +55,111 @@ where
         Self {
             graph: RwLock::new(graph),
             resources: Default::default(),
+            initializers: Default::default(),
             triggers: Default::default(),
+            plugins: Default::default(),
+            rules: Default::default(),
+            subscribers: Default::default(),
         }
     }
 }
 
 impl<T> Schema<T>
 where
-    T: Identify,
+    T: 'static + Identify + Clone,
+    T::Id: Ord + Clone,
 {
     /// Installs the given plugin in the schema.
-    pub fn install<P>(self, plugin: P) -> Self
+    ///
+    /// Fails if a plugin with the same name is already installed, or if any of the plugin's
+    /// dependencies has not been installed yet. Once installed, the triggers the plugin has
+    /// scheduled [`OnInstall`] are executed for every node already in the graph.
+    pub fn install<P>(mut self, plugin: P) -> Result<Self>
     where
         P: Plugin<T> + 'static,
     {
-        plugin.install(self)
+        let meta = PluginMeta::from_plugin(&plugin);
+        if self.plugins.contains(meta.name) {
+            return Err(Error::DuplicatedPlugin(meta.name));
+        }
+
+        if let Some(dependency) = meta
+            .dependencies
+            .iter()
+            .find(|dependency| !self.plugins.contains(dependency))
+        {
+            return Err(Error::MissingDependency {
+                plugin: meta.name,
+                dependency,
+            });
+        }
+
+        self.plugins = self.plugins.with_plugin(meta);
+
+        // Only the triggers scheduled by the plugin itself must bootstrap the graph.
+        drop(self.triggers.remove(OnInstall));
+
+        let mut schema = plugin.install(self);
+        let bootstrap = schema.triggers.remove(OnInstall);
+        schema.bootstrap(&bootstrap)?;
+
+        Ok(schema)
+    }
+
+    /// Executes the given triggers once for each node in the graph.
+    fn bootstrap(&self, triggers: &[Box<dyn Trigger<T, ()> + Send + Sync>]) -> Result<()> {
+        if triggers.is_empty() {
+            return Ok(());
+        }
+
+        let nodes: Vec<T> = self.read().into_iter().cloned().collect();
+        self.transaction().with(|ctx| {
+            nodes.into_iter().try_for_each(|node| {
+                ctx.transaction().with(|ctx| {
+                    let ctx = ctx.with_target(node);
+                    triggers
+                        .iter()
+                        .try_for_each(|trigger| trigger.execute(&ctx))
+                })
+            })
+        })
     }
+}
 
+impl<T> Schema<T>
+where
+    T: Identify,
+{
     /// Adds the given resource into the schema.
     ///
     /// If the resource already exists, the old value is overwritten.
     pub fn with_resource<R>(mut self, resource: R) -> Self
     where
-        R: 'static,
+        R: 'static + Send + Sync,
     {
         self.resources = self.resources.with_resource(resource);
         self
     }
 
+    /// Registers the given initializer for a resource that is expensive to build.
+    ///
+    /// The resource is built from the graph the first time it is requested, through
+    /// [`Schema::resource`] or a [`Context`](transaction::Context), and it does not exist yet.
+    /// Reading it straight from [`Schema::resources`] does not build it.
+    pub fn with_lazy_resource<R, F>(mut self, init: F) -> Self
+    where
+        R: 'static + Send + Sync,
+        F: 'static + Fn(&Graph<T>) -> R + Send + Sync,
+    {
+        self.initializers = self.initializers.with_initializer(init);
+        self
+    }
+
     /// Schedules the given trigger in this schema.
     pub fn with_trigger<S, Args>(
         mut self,
         scheduler: S,
-        trigger: impl Trigger<T, Args> + 'static,
+        trigger: impl Trigger<T, Args> + Send + Sync + 'static,
     ) -> Self
     where
         T: 'static,
@@ -83,16 +170,84 @@ where
         self
     }
 
+    /// Registers the given rule in this schema.
+    ///
+    /// If a rule with the same name already exists, it is replaced.
+    pub fn with_rule(mut self, rule: impl Rule<T> + Send + Sync + 'static) -> Self {
+        self.rules = self.rules.with_rule(rule);
+        self
+    }
+
     /// Returns the resource set of this schema.
     pub fn resources(&self) -> &ResourceSet {
         &self.resources
     }
 
+    /// Returns the resource of type R from this schema.
+    ///
+    /// If the resource does not exist but the schema knows how to initialize it, it is built from
+    /// the graph as it is right now. Only then it waits for any transaction in flight to complete,
+    /// so triggers must request missing lazy resources through their context instead.
+    pub fn resource<R>(&self) -> Res<R>
+    where
+        R: 'static,
+    {
+        let mut res = Res::from(self.resources());
+        self.initializers.initialize(&mut res, || self.read());
+        res
+    }
+
     /// Returns the trigger set of this schema.
     pub fn triggers(&self) -> &TriggerSet<T> {
         &self.triggers
     }
 
+    /// Returns the set of plugins installed in this schema.
+    pub fn plugins(&self) -> &PluginSet {
+        &self.plugins
+    }
+
+    /// Returns the rule set of this schema.
+    pub fn rules(&self) -> &RuleSet<T> {
+        &self.rules
+    }
+
+    /// Returns a new subscription to all the changes committed into this schema.
+    #[inline]
+    pub fn subscribe(&self) -> Subscription<T>
+    where
+        T: 'static + Clone + Send,
+        T::Id: Clone + Send,
+    {
+        self.subscribe_with(Filter::default())
+    }
+
+    /// Returns a new subscription to the changes committed into this schema that are accepted
+    /// by the given filter.
+    #[inline]
+    pub fn subscribe_with(&self, filter: Filter<T>) -> Subscription<T>
+    where
+        T: 'static + Clone + Send,
+        T::Id: Clone + Send,
+    {
+        self.subscribers.subscribe(filter)
+    }
+
+    /// Returns a snapshot of the graph as it is right now.
+    ///
+    /// The snapshot shares its structure with the schema's graph, so it is cheap to take and does
+    /// not hold any lock: it stays valid, and unchanged, while other transactions commit.
+    ///
+    /// Taking the snapshot, though, waits for any transaction in flight to complete, since
+    /// transactions hold the graph from the moment they start until they commit.
+    #[inline]
+    pub fn snapshot(&self) -> Graph<T>
+    where
+        T::Id: Ord,
+    {
+        self.read().clone()
+    }
+
     /// Returns a new transaction background.
     #[inline]
     pub fn transaction(&self) -> Background<'_, T> {
@@ -109,3 +264,219 @@ where
         self.into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            mpsc, Arc,
+        },
+        thread,
+    };
+
+    use crate::{
+        deref::WithMut,
+        graph::{
+            fixtures::{fake_node, FakeNode},
+            Graph, Source,
+        },
+        schema::{
+            ops::{
+                delete::Delete,
+                rename::Rename,
+                save::{AfterSave, Save},
+            },
+            resource::Res,
+            transaction::{Ctx, Transaction},
+            Error, Result, Schema,
+        },
+    };
+
+    #[test]
+    fn schema_should_be_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Schema<FakeNode<'static, usize>>>();
+    }
+
+    #[test]
+    fn concurrent_transactions_should_apply_all_changes() {
+        let schema: Arc<Schema<FakeNode<'static, usize>>> = Arc::new(Graph::default().into());
+        let nodes = [
+            fake_node!(0),
+            fake_node!(1),
+            fake_node!(2),
+            fake_node!(3),
+            fake_node!(4),
+            fake_node!(5),
+            fake_node!(6),
+            fake_node!(7),
+        ];
+
+        thread::scope(|scope| {
+            nodes.into_iter().for_each(|node| {
+                let schema = schema.clone();
+                scope.spawn(move || {
+                    Save::new(node)
+                        .execute(schema.transaction())
+                        .expect("save transaction should not fail");
+                });
+            });
+        });
+
+        (0..8).for_each(|id| {
+            assert!(
+                schema.read().contains(&id),
+                "node {id} should be saved by its own transaction"
+            );
+        });
+    }
+
+    #[test]
+    fn concurrent_transactions_should_share_resources() {
+        struct Counter(usize);
+
+        fn count_saves(_: Ctx<FakeNode<'static, usize>>, counter: Res<Counter>) -> Result<()> {
+            counter.with_mut(|counter| counter.0 += 1);
+            Ok(())
+        }
+
+        static EXECUTIONS: AtomicUsize = AtomicUsize::new(0);
+        const THREADS: usize = 16;
+
+        let schema = Arc::new(
+            Schema::from(Graph::default())
+                .with_resource(Counter(0))
+                .with_trigger(AfterSave, count_saves)
+                .with_trigger(AfterSave, |_: Ctx<FakeNode<'static, usize>>| {
+                    EXECUTIONS.fetch_add(1, Ordering::Relaxed);
+                    Ok(())
+                }),
+        );
+
+        thread::scope(|scope| {
+            (0..THREADS).for_each(|_| {
+                let schema = schema.clone();
+                scope.spawn(move || {
+                    Save::new(fake_node!(1))
+                        .execute(schema.transaction())
+                        .expect("save transaction should not fail");
+
+                    Delete::new(1)
+                        .execute(schema.transaction())
+                        .or_else(|err| match err {
+                            Error::Noop => Ok(()),
+                            err => Err(err),
+                        })
+                        .expect("delete transaction should not fail");
+                });
+            });
+        });
+
+        assert_eq!(
+            EXECUTIONS.load(Ordering::Relaxed),
+            THREADS,
+            "every transaction should execute its triggers"
+        );
+
+        Res::<Counter>::from(schema.resources())
+            .with_mut(|counter| {
+                assert_eq!(
+                    counter.0, THREADS,
+                    "resources should be shared between concurrent transactions"
+                )
+            })
+            .expect("resource from the schema should exist");
+    }
+
+    #[test]
+    fn snapshots_should_not_change_on_commit() {
+        let schema: Schema<FakeNode<'static, usize>> =
+            Graph::from_iter([fake_node!(1), fake_node!(2)]).into();
+
+        let snapshot = schema.snapshot();
+
+        schema
+            .transaction()
+            .with(|ctx| {
+                ctx.delete(1);
+                ctx.save(fake_node!(3));
+                Ok(())
+            })
+            .expect("transaction should not fail");
+
+        assert!(snapshot.contains(&1), "snapshot should keep deleted nodes");
+        assert!(!snapshot.contains(&3), "snapshot should not see new nodes");
+        assert_eq!(snapshot.len(), 2);
+
+        let snapshot = schema.snapshot();
+        assert!(!snapshot.contains(&1), "new snapshot should see deletions");
+        assert!(snapshot.contains(&3), "new snapshot should see new nodes");
+    }
+
+    #[test]
+    fn snapshots_should_be_readable_during_transactions() {
+        let schema: Arc<Schema<FakeNode<'static, usize>>> =
+            Arc::new(Graph::default().with_node(fake_node!(1)).into());
+
+        let snapshot = schema.snapshot();
+        let (opened_tx, opened_rx) = mpsc::channel();
+        let (read_tx, read_rx) = mpsc::channel();
+
+        thread::scope(|scope| {
+            let schema = schema.clone();
+            let writer = scope.spawn(move || {
+                schema.transaction().with(|ctx| {
+                    ctx.save(fake_node!(2));
+                    opened_tx.send(()).expect("reader should be waiting");
+                    read_rx.recv().expect("reader should not panic");
+                    Ok(())
+                })
+            });
+
+            // The writer holds the graph until the snapshot has been read.
+            opened_rx.recv().expect("writer should open a transaction");
+            assert_eq!(
+                snapshot.into_iter().count(),
+                1,
+                "snapshot should be readable while a transaction is open"
+            );
+
+            read_tx.send(()).expect("writer should be waiting");
+            writer
+                .join()
+                .expect("transaction should not panic")
+                .expect("transaction should not fail");
+        });
+
+        assert!(schema.read().contains(&2));
+    }
+
+    #[test]
+    fn renamed_nodes_should_be_moved_with_their_references() {
+        let schema: Schema<FakeNode<'static, usize>> =
+            Graph::from_iter([fake_node!(1, 2), fake_node!(2), fake_node!(3, 1)]).into();
+
+        Rename::new(4, fake_node!(4, 2))
+            .execute(schema.transaction())
+            .expect_err("renaming a missing node should fail");
+
+        Rename::new(1, fake_node!(2))
+            .execute(schema.transaction())
+            .expect_err("renaming into an existing node should fail");
+
+        Rename::new(1, fake_node!(4, 2))
+            .with_reference(fake_node!(3, 4))
+            .execute(schema.transaction())
+            .expect("rename transaction should not fail");
+
+        let graph = schema.read();
+        assert!(!graph.contains(&1), "old id should be removed");
+        assert!(graph.contains(&4), "new id should be saved");
+        assert_eq!(
+            graph.get(&3).map(|node| (node.edges_fn.unwrap())()),
+            Some(vec![4]),
+            "references should be saved"
+        );
+    }
+}
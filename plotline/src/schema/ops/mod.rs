@@ -1,4 +1,5 @@
 //! Operations to perform into a schema.
 
 pub mod delete;
+pub mod rename;
 pub mod save;
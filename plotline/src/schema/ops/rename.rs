@@ -0,0 +1,80 @@
+//! Rename transaction.
+
+use std::fmt::Debug;
+
+use crate::{
+    graph::Source,
+    id::Identify,
+    schema::{
+        ops::{delete::Delete, save::Save},
+        transaction::Transaction,
+        Error, Result,
+    },
+};
+
+/// A rename transaction, moving a node of a schema to a new id.
+///
+/// The node is deleted under its old id and saved under the new one, together with the nodes
+/// referencing it, which are expected to be rewritten to point to the new id already. Save and
+/// delete triggers are executed as usual for each of them.
+pub struct Rename<T>
+where
+    T: Identify,
+{
+    /// The id the node is being renamed from.
+    pub node_id: T::Id,
+    /// The node, as it is under its new id.
+    pub node: T,
+    /// The nodes referencing the renamed one, with their references rewritten.
+    pub references: Vec<T>,
+}
+
+impl<T> Rename<T>
+where
+    T: 'static + Identify + Clone,
+    T::Id: Debug + Ord + Clone,
+{
+    /// Executes the [`Rename`] transaction.
+    pub fn execute(self, tx: impl Transaction<Target = T>) -> Result<()> {
+        tx.with(|ctx| {
+            if !ctx.contains(&self.node_id) {
+                tracing::warn!(node_id = ?self.node_id, "node does not exist");
+                return Err(Error::Noop);
+            }
+
+            if self.node.id() != &self.node_id && ctx.contains(self.node.id()) {
+                return Err(Error::custom(format!(
+                    "node {:?} already exists",
+                    self.node.id()
+                )));
+            }
+
+            Delete::new(self.node_id).execute(ctx.transaction())?;
+            Save::new(self.node).execute(ctx.transaction())?;
+            for node in self.references {
+                Save::new(node).execute(ctx.transaction())?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+impl<T> Rename<T>
+where
+    T: Identify,
+{
+    pub fn new(node_id: T::Id, node: T) -> Self {
+        Self {
+            node_id,
+            node,
+            references: Vec::new(),
+        }
+    }
+
+    /// Saves the given node, referencing the renamed one, as part of the rename.
+    pub fn with_reference(mut self, node: T) -> Self {
+        self.references.push(node);
+        self
+    }
+}
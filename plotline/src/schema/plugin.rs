@@ -4,10 +4,349 @@ use crate::id::Identify;
 
 use super::Schema;
 
+/// Schedules a trigger for every node in the graph right after the plugin scheduling it is
+/// installed.
+///
+/// This allows plugins to index the nodes that already exist in the graph the same way they do
+/// for the nodes being saved afterwards. Triggers scheduled outside a plugin are ignored.
+pub struct OnInstall;
+
 /// A pluggin to be installed into a schema.
 pub trait Plugin<T> {
+    /// Returns the name uniquely identifying the plugin.
+    fn name(&self) -> &'static str;
+
+    /// Returns the version of the plugin.
+    fn version(&self) -> &'static str;
+
+    /// Returns the name of all the plugins that must be installed before this one.
+    fn dependencies(&self) -> Vec<&'static str> {
+        Vec::default()
+    }
+
     /// Allows the plugin to be initialized in the given schema.
     fn install(self, schema: Schema<T>) -> Schema<T>
     where
         T: Identify;
 }
+
+/// The metadata of an installed plugin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginMeta {
+    /// The name uniquely identifying the plugin.
+    pub name: &'static str,
+    /// The version of the plugin.
+    pub version: &'static str,
+    /// The name of all the plugins this one depends on.
+    pub dependencies: Vec<&'static str>,
+}
+
+impl PluginMeta {
+    /// Returns the metadata of the given plugin.
+    pub fn from_plugin<T, P>(plugin: &P) -> Self
+    where
+        P: Plugin<T>,
+    {
+        Self {
+            name: plugin.name(),
+            version: plugin.version(),
+            dependencies: plugin.dependencies(),
+        }
+    }
+}
+
+/// The set of plugins installed in a schema, in installation order.
+#[derive(Debug, Default)]
+pub struct PluginSet {
+    plugins: Vec<PluginMeta>,
+}
+
+impl<'a> IntoIterator for &'a PluginSet {
+    type Item = &'a PluginMeta;
+
+    type IntoIter = std::slice::Iter<'a, PluginMeta>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.plugins.iter()
+    }
+}
+
+impl PluginSet {
+    /// Registers the given plugin metadata.
+    pub fn with_plugin(mut self, plugin: PluginMeta) -> Self {
+        self.plugins.push(plugin);
+        self
+    }
+
+    /// Returns the metadata of the plugin with the given name, if installed.
+    pub fn get(&self, name: &str) -> Option<&PluginMeta> {
+        self.plugins.iter().find(|plugin| plugin.name == name)
+    }
+
+    /// Returns true if, and only if, a plugin with the given name is installed.
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::{
+        deref::{With, WithMut},
+        graph::{
+            fixtures::{fake_node, FakeNode},
+            Graph,
+        },
+        id::Identify,
+        schema::{
+            ops::save::{AfterSave, Save},
+            resource::Res,
+            transaction::{Ctx, Target},
+            Error, Result, Schema,
+        },
+    };
+
+    use super::{OnInstall, Plugin};
+
+    type Node = FakeNode<'static, usize>;
+
+    struct FakePlugin {
+        name: &'static str,
+        dependencies: Vec<&'static str>,
+    }
+
+    impl Plugin<Node> for FakePlugin {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn version(&self) -> &'static str {
+            "0.1.0"
+        }
+
+        fn dependencies(&self) -> Vec<&'static str> {
+            self.dependencies.clone()
+        }
+
+        fn install(self, schema: Schema<Node>) -> Schema<Node>
+        where
+            Node: Identify,
+        {
+            schema
+        }
+    }
+
+    #[test]
+    fn installed_plugins_should_be_listed_in_order() {
+        let schema = Schema::from(Graph::<Node>::default())
+            .install(FakePlugin {
+                name: "foo",
+                dependencies: Vec::default(),
+            })
+            .and_then(|schema| {
+                schema.install(FakePlugin {
+                    name: "bar",
+                    dependencies: vec!["foo"],
+                })
+            })
+            .expect("plugins should be installed");
+
+        let names: Vec<_> = schema
+            .plugins()
+            .into_iter()
+            .map(|plugin| plugin.name)
+            .collect();
+
+        assert_eq!(names, vec!["foo", "bar"]);
+
+        let bar = schema.plugins().get("bar").expect("plugin should exist");
+        assert_eq!(bar.version, "0.1.0");
+        assert_eq!(bar.dependencies, vec!["foo"]);
+    }
+
+    #[test]
+    fn duplicated_plugins_should_be_rejected() {
+        let result = Schema::from(Graph::<Node>::default())
+            .install(FakePlugin {
+                name: "foo",
+                dependencies: Vec::default(),
+            })
+            .and_then(|schema| {
+                schema.install(FakePlugin {
+                    name: "foo",
+                    dependencies: Vec::default(),
+                })
+            });
+
+        assert!(
+            matches!(result, Err(Error::DuplicatedPlugin("foo"))),
+            "installing the same plugin twice should fail"
+        );
+    }
+
+    #[test]
+    fn missing_dependencies_should_be_rejected() {
+        let result = Schema::from(Graph::<Node>::default()).install(FakePlugin {
+            name: "bar",
+            dependencies: vec!["foo"],
+        });
+
+        assert!(
+            matches!(
+                result,
+                Err(Error::MissingDependency {
+                    plugin: "bar",
+                    dependency: "foo"
+                })
+            ),
+            "installing a plugin before its dependencies should fail"
+        );
+    }
+
+    /// Counts the nodes in the graph.
+    #[derive(Default)]
+    struct NodeCount(Vec<usize>);
+
+    struct CounterPlugin;
+
+    impl CounterPlugin {
+        fn on_save(_: Ctx<Node>, target: Target<Node>, count: Res<NodeCount>) -> Result<()> {
+            (target, count).with_mut(|(node, count)| count.0.push(*node.id()));
+            Ok(())
+        }
+    }
+
+    impl Plugin<Node> for CounterPlugin {
+        fn name(&self) -> &'static str {
+            "counter"
+        }
+
+        fn version(&self) -> &'static str {
+            "0.1.0"
+        }
+
+        fn install(self, schema: Schema<Node>) -> Schema<Node>
+        where
+            Node: Identify,
+        {
+            schema
+                .with_resource(NodeCount::default())
+                .with_trigger(OnInstall, Self::on_save)
+                .with_trigger(AfterSave, Self::on_save)
+        }
+    }
+
+    #[test]
+    fn on_install_triggers_should_run_for_existing_nodes() {
+        let schema = Schema::from(Graph::from_iter([fake_node!(1), fake_node!(2)]))
+            .install(CounterPlugin)
+            .expect("plugin should be installed");
+
+        Res::<NodeCount>::from(schema.resources())
+            .with(|count| assert_eq!(count.0, vec![1, 2], "existing nodes should be indexed"))
+            .expect("resource should exist");
+
+        Save::new(fake_node!(3))
+            .execute(schema.transaction())
+            .expect("save transaction should not fail");
+
+        Res::<NodeCount>::from(schema.resources())
+            .with(|count| {
+                assert_eq!(
+                    count.0,
+                    vec![1, 2, 3],
+                    "on-install triggers should not run once installed"
+                )
+            })
+            .expect("resource should exist");
+    }
+
+    #[test]
+    fn on_install_triggers_should_run_once() {
+        static EXECUTIONS: AtomicUsize = AtomicUsize::new(0);
+
+        struct OncePlugin;
+
+        impl Plugin<Node> for OncePlugin {
+            fn name(&self) -> &'static str {
+                "once"
+            }
+
+            fn version(&self) -> &'static str {
+                "0.1.0"
+            }
+
+            fn install(self, schema: Schema<Node>) -> Schema<Node>
+            where
+                Node: Identify,
+            {
+                schema.with_trigger(OnInstall, |_: Ctx<Node>| {
+                    EXECUTIONS.fetch_add(1, Ordering::Relaxed);
+                    Ok(())
+                })
+            }
+        }
+
+        Schema::from(Graph::from_iter([fake_node!(1), fake_node!(2)]))
+            .install(OncePlugin)
+            .and_then(|schema| schema.install(CounterPlugin))
+            .expect("plugins should be installed");
+
+        assert_eq!(
+            EXECUTIONS.load(Ordering::Relaxed),
+            2,
+            "on-install triggers should run once per existing node"
+        );
+    }
+
+    #[test]
+    fn on_install_triggers_outside_plugins_should_be_ignored() {
+        static EXECUTIONS: AtomicUsize = AtomicUsize::new(0);
+
+        Schema::from(Graph::from_iter([fake_node!(1), fake_node!(2)]))
+            .with_trigger(OnInstall, |_: Ctx<Node>| {
+                EXECUTIONS.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            })
+            .install(CounterPlugin)
+            .expect("plugin should be installed");
+
+        assert_eq!(
+            EXECUTIONS.load(Ordering::Relaxed),
+            0,
+            "on-install triggers scheduled outside a plugin should not run"
+        );
+    }
+
+    #[test]
+    fn failing_on_install_triggers_should_fail_installation() {
+        struct FailingPlugin;
+
+        impl Plugin<Node> for FailingPlugin {
+            fn name(&self) -> &'static str {
+                "failing"
+            }
+
+            fn version(&self) -> &'static str {
+                "0.1.0"
+            }
+
+            fn install(self, schema: Schema<Node>) -> Schema<Node>
+            where
+                Node: Identify,
+            {
+                schema.with_trigger(OnInstall, |_: Ctx<Node>| {
+                    Err(Error::custom("failed bootstrap"))
+                })
+            }
+        }
+
+        let result = Schema::from(Graph::from_iter([fake_node!(1)])).install(FailingPlugin);
+        assert!(
+            matches!(result, Err(Error::Msg(_))),
+            "on-install errors should be propagated"
+        );
+    }
+}
@@ -4,47 +4,157 @@ use std::{
     any::{Any, TypeId},
     collections::BTreeMap,
     marker::PhantomData,
+    ops::Deref,
     sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
 };
 
 use crate::{
     deref::{ReadOnly, ReadWrite, TryDeref, TryDerefMut, With},
+    graph::Graph,
     id::Identify,
 };
 
 use super::transaction::Context;
 
+/// The type-erased value of a resource.
+type Resource = Box<dyn Any + Send + Sync>;
+
+/// The shared lock of a resource.
+type ResourceLock = Arc<RwLock<Resource>>;
+
 /// Represents a set of arbitrary resources.
 #[derive(Debug, Default)]
 pub struct ResourceSet {
-    resources: BTreeMap<TypeId, Arc<RwLock<Box<dyn Any>>>>,
+    resources: Arc<RwLock<BTreeMap<TypeId, ResourceLock>>>,
 }
 
 impl ResourceSet {
     /// Registers the given resource.
     ///
     /// This methos overwrites any older value for the same resource type.
-    #[allow(clippy::arc_with_non_send_sync)]
-    pub fn with_resource<R>(mut self, resource: R) -> Self
+    pub fn with_resource<R>(self, resource: R) -> Self
+    where
+        R: 'static + Send + Sync,
+    {
+        self.apply(ResourceOperation::insert(resource));
+        self
+    }
+
+    /// Applies the given operation into the set.
+    pub(crate) fn apply(&self, op: ResourceOperation) {
+        let mut resources = match self.resources.write() {
+            Ok(resources) => resources,
+            Err(err) => err.into_inner(),
+        };
+
+        match op {
+            ResourceOperation::Insert(type_id, resource) => {
+                resources.insert(type_id, Arc::new(RwLock::new(resource)));
+            }
+            ResourceOperation::Remove(type_id) => {
+                resources.remove(&type_id);
+            }
+        }
+    }
+}
+
+/// Represents an operation over the resources of a schema.
+pub(crate) enum ResourceOperation {
+    Insert(TypeId, Resource),
+    Remove(TypeId),
+}
+
+impl ResourceOperation {
+    /// Returns the operation inserting, or replacing, the given resource.
+    pub(crate) fn insert<R>(resource: R) -> Self
+    where
+        R: 'static + Send + Sync,
+    {
+        Self::Insert(TypeId::of::<R>(), Box::new(resource))
+    }
+
+    /// Returns the operation removing the resource of type R.
+    pub(crate) fn remove<R>() -> Self
     where
         R: 'static,
     {
-        let type_id = TypeId::of::<R>();
-        self.resources
-            .insert(type_id, Arc::new(RwLock::new(Box::new(resource))));
+        Self::Remove(TypeId::of::<R>())
+    }
+}
+
+/// Builds a resource from the graph of the schema.
+type Initializer<T> = Box<dyn Fn(&Graph<T>) -> Resource + Send + Sync>;
+
+/// Represents a set of initializers for resources that are built on first use.
+pub struct InitializerSet<T>
+where
+    T: Identify,
+{
+    initializers: BTreeMap<TypeId, Initializer<T>>,
+}
+
+impl<T> Default for InitializerSet<T>
+where
+    T: Identify,
+{
+    fn default() -> Self {
+        Self {
+            initializers: Default::default(),
+        }
+    }
+}
+
+impl<T> InitializerSet<T>
+where
+    T: Identify,
+{
+    /// Registers the given initializer for the resource of type R.
+    ///
+    /// This method overwrites any older initializer for the same resource type.
+    pub fn with_initializer<R, F>(mut self, init: F) -> Self
+    where
+        R: 'static + Send + Sync,
+        F: 'static + Fn(&Graph<T>) -> R + Send + Sync,
+    {
+        self.initializers.insert(
+            TypeId::of::<R>(),
+            Box::new(move |graph| Box::new(init(graph))),
+        );
+
         self
     }
+
+    /// Makes sure the given resource exists, initializing it from the graph returned by the given
+    /// closure if necessary.
+    ///
+    /// The closure is only called when the resource has to be initialized.
+    pub(crate) fn initialize<R, G>(&self, res: &mut Res<R>, graph: impl FnOnce() -> G)
+    where
+        R: 'static,
+        G: Deref<Target = Graph<T>>,
+    {
+        if res.lock.is_some() {
+            return;
+        }
+
+        let Some(init) = self.initializers.get(&TypeId::of::<R>()) else {
+            return;
+        };
+
+        res.get_or_insert_resource(|| init(&graph()));
+    }
 }
 
 /// A resource that may, or may not, exist in the schema.
 pub struct Res<T> {
-    lock: Option<Arc<RwLock<Box<dyn Any>>>>,
+    resources: Arc<RwLock<BTreeMap<TypeId, ResourceLock>>>,
+    lock: Option<ResourceLock>,
     _type: PhantomData<T>,
 }
 
 /// Holds a read-only access to a resource.
 pub struct ResReadGuard<'a, T> {
-    guard: Option<RwLockReadGuard<'a, Box<dyn Any>>>,
+    guard: Option<RwLockReadGuard<'a, Resource>>,
     _type: PhantomData<T>,
 }
 
@@ -95,7 +205,7 @@ where
 
 /// Holds a read-write access to a resource.
 pub struct ResWriteGuard<'a, T> {
-    guard: Option<RwLockWriteGuard<'a, Box<dyn Any>>>,
+    guard: Option<RwLockWriteGuard<'a, Resource>>,
     _type: PhantomData<T>,
 }
 
@@ -161,6 +271,41 @@ where
     pub fn exists(&self) -> bool {
         self.with(|_| true).unwrap_or_default()
     }
+
+    /// Makes sure the resource exists, inserting the value returned by the given closure into
+    /// the schema if it does not.
+    ///
+    /// Unlike the resources registered through a [`Context`], the inserted value is available
+    /// right away and it is kept even if the transaction fails.
+    pub fn get_or_insert_with<F>(&mut self, f: F) -> &mut Self
+    where
+        T: Send + Sync,
+        F: FnOnce() -> T,
+    {
+        if self.lock.is_none() {
+            self.get_or_insert_resource(|| Box::new(f()));
+        }
+
+        self
+    }
+
+    /// Sets the lock of the resource, inserting the value returned by the given closure if the
+    /// resource does not exist in the set.
+    fn get_or_insert_resource<F>(&mut self, f: F)
+    where
+        F: FnOnce() -> Resource,
+    {
+        let mut resources = match self.resources.write() {
+            Ok(resources) => resources,
+            Err(err) => err.into_inner(),
+        };
+
+        let lock = resources
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Arc::new(RwLock::new(f())));
+
+        self.lock = Some(lock.clone());
+    }
 }
 
 impl<T> From<&ResourceSet> for Res<T>
@@ -168,8 +313,14 @@ where
     T: 'static,
 {
     fn from(set: &ResourceSet) -> Self {
+        let lock = match set.resources.read() {
+            Ok(resources) => resources.get(&TypeId::of::<T>()).cloned(),
+            Err(err) => err.into_inner().get(&TypeId::of::<T>()).cloned(),
+        };
+
         Self {
-            lock: set.resources.get(&TypeId::of::<T>()).cloned(),
+            resources: set.resources.clone(),
+            lock,
             _type: PhantomData,
         }
     }
@@ -181,17 +332,26 @@ where
     R: 'static,
 {
     fn from(ctx: &Context<T>) -> Self {
-        ctx.resources().into()
+        ctx.resource()
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
     use crate::{
         deref::{With, WithMut},
-        graph::Graph,
+        graph::{
+            fixtures::{fake_node, FakeNode},
+            Graph,
+        },
         id::fixtures::IndentifyMock,
-        schema::{resource::Res, Schema},
+        schema::{
+            resource::Res,
+            transaction::{Context, Transaction},
+            Error, Result, Schema,
+        },
     };
 
     #[test]
@@ -229,4 +389,171 @@ mod tests {
         })
         .expect("resource from the schema should exists");
     }
+
+    #[test]
+    fn context_resources_should_apply_on_commit() {
+        struct Foo(usize);
+        struct Bar;
+
+        let schema = Schema::from(Graph::<IndentifyMock<usize>>::default()).with_resource(Bar);
+
+        schema
+            .transaction()
+            .with(|ctx| {
+                ctx.insert_resource(Foo(1));
+                ctx.remove_resource::<Bar>();
+
+                assert!(
+                    !Res::<Foo>::from(&ctx).exists(),
+                    "inserted resource should not exist before commit"
+                );
+
+                assert!(
+                    Res::<Bar>::from(&ctx).exists(),
+                    "removed resource should exist before commit"
+                );
+
+                Ok(())
+            })
+            .expect("transaction should not fail");
+
+        Res::<Foo>::from(schema.resources())
+            .with(|foo| assert_eq!(foo.0, 1, "inserted resource should exist after commit"))
+            .expect("inserted resource should exist after commit");
+
+        assert!(
+            !Res::<Bar>::from(schema.resources()).exists(),
+            "removed resource should not exist after commit"
+        );
+    }
+
+    #[test]
+    fn context_resources_should_replace_older_values() {
+        struct Foo(usize);
+
+        let schema = Schema::from(Graph::<IndentifyMock<usize>>::default()).with_resource(Foo(0));
+
+        schema
+            .transaction()
+            .with(|ctx| {
+                ctx.transaction().with(|ctx| {
+                    ctx.insert_resource(Foo(1));
+                    Ok(())
+                })
+            })
+            .expect("transaction should not fail");
+
+        Res::<Foo>::from(schema.resources())
+            .with(|foo| assert_eq!(foo.0, 1, "resource should be replaced after commit"))
+            .expect("resource should exist after commit");
+    }
+
+    #[test]
+    fn uncommitted_context_resources_should_not_apply() {
+        struct Foo;
+
+        let schema = Schema::from(Graph::<IndentifyMock<usize>>::default());
+
+        schema
+            .transaction()
+            .with(|ctx| {
+                ctx.insert_resource(Foo);
+                Result::<()>::Err(Error::custom("failed transaction"))
+            })
+            .expect_err("transaction error should be propagated");
+
+        assert!(
+            !Res::<Foo>::from(schema.resources()).exists(),
+            "uncommitted transaction should not insert resources"
+        );
+    }
+
+    #[test]
+    fn resource_get_or_insert_with() {
+        struct Foo(usize);
+
+        let schema = Schema::from(Graph::<IndentifyMock<usize>>::default());
+
+        Res::<Foo>::from(schema.resources())
+            .get_or_insert_with(|| Foo(1))
+            .with(|foo| assert_eq!(foo.0, 1, "missing resource should be inserted"))
+            .expect("inserted resource should exist");
+
+        Res::<Foo>::from(schema.resources())
+            .get_or_insert_with(|| Foo(2))
+            .with(|foo| assert_eq!(foo.0, 1, "existing resource should be kept"))
+            .expect("resource should exist");
+    }
+
+    #[test]
+    fn lazy_resource_should_be_initialized_on_first_use() {
+        static INITS: AtomicUsize = AtomicUsize::new(0);
+
+        struct NodeCount(usize);
+
+        let schema = Schema::from(Graph::from_iter([fake_node!(1), fake_node!(2)]))
+            .with_lazy_resource(|graph: &Graph<FakeNode<usize>>| {
+                INITS.fetch_add(1, Ordering::Relaxed);
+                NodeCount(graph.into_iter().count())
+            });
+
+        assert!(
+            !Res::<NodeCount>::from(schema.resources()).exists(),
+            "lazy resource should not exist before first use"
+        );
+
+        (0..2).for_each(|_| {
+            schema
+                .transaction()
+                .with(|ctx: Context<'_, _>| {
+                    Res::<NodeCount>::from(&ctx)
+                        .with(|count| {
+                            assert_eq!(count.0, 2, "lazy resource should be built from the graph")
+                        })
+                        .expect("lazy resource should exist on first use");
+
+                    Ok(())
+                })
+                .expect("transaction should not fail");
+        });
+
+        assert_eq!(
+            INITS.load(Ordering::Relaxed),
+            1,
+            "lazy resource should be initialized once"
+        );
+    }
+
+    #[test]
+    fn lazy_resource_should_be_initialized_outside_transactions() {
+        struct NodeCount(usize);
+
+        let schema = Schema::from(Graph::from_iter([fake_node!(1), fake_node!(2)]))
+            .with_lazy_resource(|graph: &Graph<FakeNode<usize>>| {
+                NodeCount(graph.into_iter().count())
+            });
+
+        schema
+            .resource::<NodeCount>()
+            .with(|count| assert_eq!(count.0, 2, "lazy resource should be built from the graph"))
+            .expect("lazy resource should exist on first use");
+
+        assert!(
+            Res::<NodeCount>::from(schema.resources()).exists(),
+            "lazy resource should be kept once initialized"
+        );
+
+        // The transaction holds the graph, so reading it again would never return.
+        schema
+            .transaction()
+            .with(|_| {
+                assert!(
+                    schema.resource::<NodeCount>().exists(),
+                    "existing resources should not wait for the graph"
+                );
+
+                Ok(())
+            })
+            .expect("transaction should not fail");
+    }
 }
@@ -0,0 +1,225 @@
+//! Consistency rules.
+
+use crate::{graph::Graph, id::Identify};
+
+/// How serious the breach of a rule is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A breach of a rule by a node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation<Id> {
+    /// The name of the breached rule.
+    pub rule: &'static str,
+    pub severity: Severity,
+    /// The id of the node breaching the rule.
+    pub id: Id,
+    /// The line of the node the breach is at, if any.
+    pub line: Option<usize>,
+    pub message: String,
+    /// Whether the rule is able to fix the breach.
+    pub fixable: bool,
+}
+
+impl<Id> Violation<Id> {
+    /// Returns a new violation of the given severity.
+    ///
+    /// The name of the rule is set once the violation is reported by a [`RuleSet`].
+    pub fn new(severity: Severity, id: Id, message: impl Into<String>) -> Self {
+        Self {
+            rule: Default::default(),
+            severity,
+            id,
+            line: None,
+            message: message.into(),
+            fixable: false,
+        }
+    }
+
+    /// Returns a new violation with the [`Severity::Error`] severity.
+    pub fn error(id: Id, message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, id, message)
+    }
+
+    /// Returns a new violation with the [`Severity::Warning`] severity.
+    pub fn warning(id: Id, message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, id, message)
+    }
+
+    /// Sets the line of the node the breach is at.
+    pub fn with_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    /// Marks the violation as fixable by its rule.
+    pub fn with_fix(mut self) -> Self {
+        self.fixable = true;
+        self
+    }
+}
+
+/// A rule the nodes of a graph are expected to follow.
+pub trait Rule<T>
+where
+    T: Identify,
+{
+    /// Returns the name uniquely identifying the rule.
+    fn name(&self) -> &'static str;
+
+    /// Returns all the breaches of the rule in the given graph.
+    fn check(&self, graph: &Graph<T>) -> Vec<Violation<T::Id>>;
+
+    /// Returns the nodes to be saved in order to fix the given violation, if the rule knows how
+    /// to.
+    fn fix(&self, graph: &Graph<T>, violation: &Violation<T::Id>) -> Option<Vec<T>> {
+        let _ = (graph, violation);
+        None
+    }
+}
+
+/// The set of rules of a schema, in registration order.
+pub struct RuleSet<T> {
+    rules: Vec<Box<dyn Rule<T> + Send + Sync>>,
+}
+
+impl<T> Default for RuleSet<T> {
+    fn default() -> Self {
+        Self {
+            rules: Default::default(),
+        }
+    }
+}
+
+impl<T> RuleSet<T>
+where
+    T: Identify,
+{
+    /// Registers the given rule.
+    ///
+    /// If a rule with the same name already exists, it is replaced.
+    pub fn with_rule(mut self, rule: impl Rule<T> + Send + Sync + 'static) -> Self {
+        let rule: Box<dyn Rule<T> + Send + Sync> = Box::new(rule);
+        match self
+            .rules
+            .iter_mut()
+            .find(|registered| registered.name() == rule.name())
+        {
+            Some(registered) => *registered = rule,
+            None => self.rules.push(rule),
+        }
+
+        self
+    }
+
+    /// Returns the rule with the given name, if any.
+    pub fn get(&self, name: &str) -> Option<&(dyn Rule<T> + Send + Sync)> {
+        self.rules
+            .iter()
+            .find(|rule| rule.name() == name)
+            .map(AsRef::as_ref)
+    }
+
+    /// Returns the names of all the rules in the set.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.rules.iter().map(|rule| rule.name())
+    }
+
+    /// Returns all the breaches of every rule in the given graph.
+    pub fn check(&self, graph: &Graph<T>) -> Vec<Violation<T::Id>> {
+        self.rules
+            .iter()
+            .flat_map(|rule| {
+                rule.check(graph).into_iter().map(|mut violation| {
+                    violation.rule = rule.name();
+                    violation
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the nodes to be saved in order to fix the given violation, if its rule knows how
+    /// to.
+    pub fn fix(&self, graph: &Graph<T>, violation: &Violation<T::Id>) -> Option<Vec<T>> {
+        self.get(violation.rule)?.fix(graph, violation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graph::{
+            fixtures::{fake_node, FakeEdge, FakeNode},
+            Graph,
+        },
+        id::Identify,
+        property::Property,
+    };
+
+    use super::{Rule, RuleSet, Severity, Violation};
+
+    type Node = FakeNode<'static, usize>;
+
+    /// Nodes must not point to themselves.
+    struct NoLoops;
+
+    impl Rule<Node> for NoLoops {
+        fn name(&self) -> &'static str {
+            "no-loops"
+        }
+
+        fn check(&self, graph: &Graph<Node>) -> Vec<Violation<usize>> {
+            graph
+                .into_iter()
+                .filter(|node| {
+                    FakeEdge::all(*node)
+                        .iter()
+                        .any(|edge| edge.id() == node.id())
+                })
+                .map(|node| Violation::error(*node.id(), "node points to itself").with_fix())
+                .collect()
+        }
+
+        fn fix(&self, _: &Graph<Node>, violation: &Violation<usize>) -> Option<Vec<Node>> {
+            (violation.id == 1).then(|| vec![fake_node!(1)])
+        }
+    }
+
+    #[test]
+    fn violations_should_be_reported_by_rule() {
+        let graph = Graph::from_iter([fake_node!(1, 1), fake_node!(2, 1), fake_node!(3, 3)]);
+        let rules = RuleSet::default().with_rule(NoLoops).with_rule(NoLoops);
+
+        assert_eq!(
+            rules.names().collect::<Vec<_>>(),
+            vec!["no-loops"],
+            "rules with the same name should be replaced"
+        );
+
+        let violations = rules.check(&graph);
+        assert_eq!(
+            violations
+                .iter()
+                .map(|violation| (violation.rule, violation.severity, violation.id))
+                .collect::<Vec<_>>(),
+            vec![
+                ("no-loops", Severity::Error, 1),
+                ("no-loops", Severity::Error, 3)
+            ]
+        );
+
+        assert_eq!(
+            rules.fix(&graph, &violations[0]).map(|nodes| nodes.len()),
+            Some(1)
+        );
+        assert!(rules.fix(&graph, &violations[1]).is_none());
+    }
+}
@@ -0,0 +1,483 @@
+//! Subscriptions to the changes committed into a schema.
+
+use std::{
+    collections::BTreeSet,
+    fmt::Debug,
+    sync::{
+        mpsc::{self, Receiver, RecvError, RecvTimeoutError, TryRecvError},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use crate::{id::Identify, prefix::Prefixed};
+
+use super::transaction::{Operation, OperationKind};
+
+/// The set of operations committed by a single transaction.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        transparent,
+        bound(
+            serialize = "T: serde::Serialize, T::Id: serde::Serialize",
+            deserialize = "T: serde::Deserialize<'de>, T::Id: serde::Deserialize<'de>"
+        )
+    )
+)]
+pub struct Changeset<T>
+where
+    T: Identify,
+{
+    operations: Vec<Operation<T>>,
+}
+
+impl<T> Debug for Changeset<T>
+where
+    T: Identify + Debug,
+    T::Id: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(&self.operations).finish()
+    }
+}
+
+impl<T> Clone for Changeset<T>
+where
+    T: Identify + Clone,
+    T::Id: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            operations: self.operations.clone(),
+        }
+    }
+}
+
+impl<T> IntoIterator for Changeset<T>
+where
+    T: Identify,
+{
+    type Item = Operation<T>;
+
+    type IntoIter = std::vec::IntoIter<Operation<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.operations.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Changeset<T>
+where
+    T: Identify,
+{
+    type Item = &'a Operation<T>;
+
+    type IntoIter = std::slice::Iter<'a, Operation<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.operations.iter()
+    }
+}
+
+impl<T> FromIterator<Operation<T>> for Changeset<T>
+where
+    T: Identify,
+{
+    fn from_iter<I: IntoIterator<Item = Operation<T>>>(operations: I) -> Self {
+        Self {
+            operations: operations.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> Changeset<T>
+where
+    T: Identify,
+{
+    /// Returns the amount of operations in the changeset.
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    /// Returns true if, and only if, the changeset has no operations.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+}
+
+/// Decides whether an id is accepted or not.
+type IdPredicate<Id> = Box<dyn Fn(&Id) -> bool + Send + Sync>;
+
+/// Decides which operations are notified to a subscription.
+pub struct Filter<T>
+where
+    T: Identify,
+{
+    kinds: BTreeSet<OperationKind>,
+    ids: Vec<IdPredicate<T::Id>>,
+}
+
+impl<T> Default for Filter<T>
+where
+    T: Identify,
+{
+    fn default() -> Self {
+        Self {
+            kinds: Default::default(),
+            ids: Default::default(),
+        }
+    }
+}
+
+impl<T> Filter<T>
+where
+    T: Identify,
+{
+    /// Accepts the operations of the given kind.
+    ///
+    /// If no kind is set, operations of any kind are accepted.
+    pub fn with_kind(mut self, kind: OperationKind) -> Self {
+        self.kinds.insert(kind);
+        self
+    }
+
+    /// Accepts only the operations whose id satisfies the given predicate.
+    pub fn with_id<F>(mut self, predicate: F) -> Self
+    where
+        F: 'static + Fn(&T::Id) -> bool + Send + Sync,
+    {
+        self.ids.push(Box::new(predicate));
+        self
+    }
+
+    /// Accepts only the operations whose id starts with the given prefix.
+    pub fn with_prefix(self, prefix: T::Id) -> Self
+    where
+        T::Id: 'static + Prefixed + Send + Sync,
+    {
+        self.with_id(move |id| id.starts_with(&prefix))
+    }
+
+    /// Returns true if, and only if, the given operation is accepted by the filter.
+    fn accepts(&self, op: &Operation<T>) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(&op.kind()))
+            && self.ids.iter().all(|predicate| predicate(op.id()))
+    }
+}
+
+/// A subscription to the changes committed into a schema.
+///
+/// The subscription ends once the schema is dropped.
+pub struct Subscription<T>
+where
+    T: Identify,
+{
+    receiver: Receiver<Changeset<T>>,
+}
+
+impl<T> Iterator for Subscription<T>
+where
+    T: Identify,
+{
+    type Item = Changeset<T>;
+
+    /// Blocks until the next changeset is committed.
+    fn next(&mut self) -> Option<Self::Item> {
+        self.recv().ok()
+    }
+}
+
+impl<T> Subscription<T>
+where
+    T: Identify,
+{
+    /// Blocks until the next changeset is committed.
+    pub fn recv(&self) -> Result<Changeset<T>, RecvError> {
+        self.receiver.recv()
+    }
+
+    /// Returns the next changeset, if any, without blocking.
+    pub fn try_recv(&self) -> Result<Changeset<T>, TryRecvError> {
+        self.receiver.try_recv()
+    }
+
+    /// Blocks until the next changeset is committed or the given timeout expires.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<Changeset<T>, RecvTimeoutError> {
+        self.receiver.recv_timeout(timeout)
+    }
+
+    /// Returns an iterator over all the pending changesets, without blocking.
+    pub fn try_iter(&self) -> impl Iterator<Item = Changeset<T>> + '_ {
+        self.receiver.try_iter()
+    }
+}
+
+/// Notifies the given operations to a subscription, returning false if the subscription has
+/// been dropped.
+type Subscriber<T> = Box<dyn Fn(&[Operation<T>]) -> bool + Send + Sync>;
+
+/// Represents the set of subscriptions to a schema.
+pub struct SubscriberSet<T>
+where
+    T: Identify,
+{
+    subscribers: Mutex<Vec<Subscriber<T>>>,
+}
+
+impl<T> Default for SubscriberSet<T>
+where
+    T: Identify,
+{
+    fn default() -> Self {
+        Self {
+            subscribers: Default::default(),
+        }
+    }
+}
+
+impl<T> SubscriberSet<T>
+where
+    T: Identify,
+{
+    /// Returns a new subscription to the operations accepted by the given filter.
+    pub fn subscribe(&self, filter: Filter<T>) -> Subscription<T>
+    where
+        T: 'static + Clone + Send,
+        T::Id: Clone + Send,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let subscriber: Subscriber<T> = Box::new(move |ops| {
+            let changeset: Changeset<T> = ops
+                .iter()
+                .filter(|op| filter.accepts(op))
+                .cloned()
+                .collect();
+
+            changeset.is_empty() || sender.send(changeset).is_ok()
+        });
+
+        let mut subscribers = match self.subscribers.lock() {
+            Ok(subscribers) => subscribers,
+            Err(err) => err.into_inner(),
+        };
+
+        subscribers.push(subscriber);
+        Subscription { receiver }
+    }
+
+    /// Notifies the given operations to all the subscriptions, dropping those that have ended.
+    pub(crate) fn notify(&self, ops: &[Operation<T>]) {
+        if ops.is_empty() {
+            return;
+        }
+
+        let mut subscribers = match self.subscribers.lock() {
+            Ok(subscribers) => subscribers,
+            Err(err) => err.into_inner(),
+        };
+
+        subscribers.retain(|subscriber| subscriber(ops));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc::TryRecvError, thread};
+
+    use crate::{
+        graph::{
+            fixtures::{fake_node, FakeNode},
+            Graph,
+        },
+        id::Identify,
+        schema::{
+            transaction::{Operation, OperationKind, Transaction},
+            Error, Result, Schema,
+        },
+    };
+
+    use super::Filter;
+
+    type Node = FakeNode<'static, usize>;
+
+    #[test]
+    fn committed_changes_should_be_notified() {
+        let schema: Schema<Node> = Graph::default().with_node(fake_node!(1)).into();
+        let subscription = schema.subscribe();
+
+        schema
+            .transaction()
+            .with(|ctx| {
+                ctx.delete(1);
+                ctx.save(fake_node!(2));
+                Ok(())
+            })
+            .expect("transaction should not fail");
+
+        let changeset = subscription
+            .try_recv()
+            .expect("committed changes should be notified");
+
+        let ops: Vec<_> = changeset
+            .into_iter()
+            .map(|op| (op.kind(), *op.id()))
+            .collect();
+
+        assert_eq!(
+            ops,
+            vec![(OperationKind::Delete, 1), (OperationKind::Save, 2)],
+            "all the operations should be notified in order"
+        );
+    }
+
+    #[test]
+    fn uncommitted_changes_should_not_be_notified() {
+        let schema: Schema<Node> = Graph::default().into();
+        let subscription = schema.subscribe();
+
+        schema
+            .transaction()
+            .with(|ctx| {
+                ctx.save(fake_node!(1));
+                Result::<()>::Err(Error::custom("failed transaction"))
+            })
+            .expect_err("transaction error should be propagated");
+
+        assert!(
+            matches!(subscription.try_recv(), Err(TryRecvError::Empty)),
+            "uncommitted changes should not be notified"
+        );
+    }
+
+    #[test]
+    fn filtered_out_changes_should_not_be_notified() {
+        let schema: Schema<Node> = Graph::default().with_node(fake_node!(1)).into();
+
+        let deletions = schema.subscribe_with(Filter::default().with_kind(OperationKind::Delete));
+        let even = schema.subscribe_with(Filter::default().with_id(|id| id % 2 == 0));
+
+        schema
+            .transaction()
+            .with(|ctx| {
+                ctx.save(fake_node!(3));
+                Ok(())
+            })
+            .expect("transaction should not fail");
+
+        schema
+            .transaction()
+            .with(|ctx| {
+                ctx.delete(1);
+                ctx.save(fake_node!(2));
+                Ok(())
+            })
+            .expect("transaction should not fail");
+
+        let changeset = deletions.try_recv().expect("deletion should be notified");
+        assert!(
+            changeset
+                .into_iter()
+                .all(|op| matches!(op, Operation::Delete(1))),
+            "only deletions should be notified"
+        );
+
+        assert!(
+            matches!(deletions.try_recv(), Err(TryRecvError::Empty)),
+            "empty changesets should not be notified"
+        );
+
+        let changeset = even.try_recv().expect("even ids should be notified");
+        assert!(
+            changeset.into_iter().all(|op| *op.id() == 2),
+            "only even ids should be notified"
+        );
+    }
+
+    #[test]
+    fn subscriptions_should_be_iterable() {
+        let schema: Schema<Node> = Graph::default().into();
+        let subscription = schema.subscribe();
+
+        thread::scope(|scope| {
+            let handle = scope.spawn(move || {
+                subscription
+                    .take(2)
+                    .map(|changeset| changeset.len())
+                    .sum::<usize>()
+            });
+
+            (0..2).for_each(|_| {
+                schema
+                    .transaction()
+                    .with(|ctx| {
+                        ctx.save(fake_node!(1));
+                        Ok(())
+                    })
+                    .expect("transaction should not fail");
+            });
+
+            assert_eq!(
+                handle.join().expect("subscriber should not panic"),
+                2,
+                "all committed changesets should be received"
+            );
+        });
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn changesets_should_be_serializable() {
+        #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+        struct Node {
+            id: usize,
+            name: String,
+        }
+
+        impl Identify for Node {
+            type Id = usize;
+
+            fn id(&self) -> &Self::Id {
+                &self.id
+            }
+        }
+
+        let schema: Schema<Node> = Graph::default()
+            .with_node(Node {
+                id: 1,
+                name: "alice".into(),
+            })
+            .into();
+
+        let subscription = schema.subscribe();
+        schema
+            .transaction()
+            .with(|ctx| {
+                ctx.delete(1);
+                ctx.save(Node {
+                    id: 2,
+                    name: "bob".into(),
+                });
+                Ok(())
+            })
+            .expect("transaction should not fail");
+
+        let changeset = subscription
+            .try_recv()
+            .expect("committed changes should be notified");
+
+        let json = serde_json::to_string(&changeset).expect("changeset should serialize");
+        assert_eq!(json, r#"[{"Delete":1},{"Save":{"id":2,"name":"bob"}}]"#);
+
+        let changeset: super::Changeset<Node> =
+            serde_json::from_str(&json).expect("changeset should deserialize");
+        assert_eq!(changeset.len(), 2);
+
+        let json = serde_json::to_string(&schema.snapshot()).expect("graph should serialize");
+        assert_eq!(json, r#"[{"id":2,"name":"bob"}]"#);
+
+        let graph: Graph<Node> = serde_json::from_str(&json).expect("graph should deserialize");
+        assert_eq!(graph.len(), 1);
+    }
+}
@@ -1,6 +1,10 @@
 //! Transaction definition.
 
-use std::sync::{Arc, OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::{
+    borrow::Cow,
+    fmt::Debug,
+    sync::{Arc, OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
 
 use crate::{
     deref::{ReadOnly, ReadWrite, TryDeref, TryDerefMut},
@@ -8,7 +12,12 @@ use crate::{
     id::Identify,
 };
 
-use super::{guard::SchemaWriteGuard, resource::ResourceSet, trigger::TriggerSet, Result, Schema};
+use super::{
+    guard::SchemaWriteGuard,
+    resource::{Res, ResourceOperation, ResourceSet},
+    trigger::TriggerSet,
+    Result, Schema,
+};
 
 /// Represents a set of operations that must be perfomed as a whole.
 pub trait Transaction: Sized {
@@ -29,6 +38,7 @@ where
     schema: &'a Schema<T>,
     guard: OnceLock<SchemaWriteGuard<'a, T>>,
     operations: Arc<RwLock<Vec<Operation<T>>>>,
+    resource_operations: Arc<RwLock<Vec<ResourceOperation>>>,
 }
 
 impl<'a, T> From<&'a Schema<T>> for Background<'a, T>
@@ -40,6 +50,7 @@ where
             schema,
             guard: Default::default(),
             operations: Default::default(),
+            resource_operations: Default::default(),
         }
     }
 }
@@ -72,27 +83,21 @@ where
             return;
         };
 
-        let Some(ops) = Arc::into_inner(self.operations) else {
-            tracing::error!("commiting transaction with contexts yet in use");
+        let Some((ops, resource_ops)) =
+            into_inner(self.operations).zip(into_inner(self.resource_operations))
+        else {
             return;
         };
 
-        let ops = match ops.into_inner() {
-            Ok(ops) => ops,
-            Err(err) => {
-                tracing::error!(error = err.to_string(), "committing poisoned transaction");
-                return;
-            }
-        };
-
+        self.schema.subscribers.notify(&ops);
         ops.into_iter().for_each(|op| match op {
-            Operation::Save(node) => {
-                guard.insert(node);
-            }
-            Operation::Delete(node_id) => {
-                guard.remove(&node_id);
-            }
+            Operation::Save(node) => guard.store(node),
+            Operation::Delete(node_id) => guard.discard(&node_id),
         });
+
+        resource_ops
+            .into_iter()
+            .for_each(|op| self.schema.resources.apply(op));
     }
 }
 
@@ -103,6 +108,7 @@ where
 {
     context: &'a Context<'a, T>,
     operations: Arc<RwLock<Vec<Operation<T>>>>,
+    resource_operations: Arc<RwLock<Vec<ResourceOperation>>>,
 }
 
 impl<'a, T> From<&'a Context<'a, T>> for Foreground<'a, T>
@@ -113,6 +119,7 @@ where
         Foreground {
             context,
             operations: Default::default(),
+            resource_operations: Default::default(),
         }
     }
 }
@@ -138,22 +145,25 @@ where
     T: Identify,
 {
     fn commit(self) {
-        let Some(ops) = Arc::into_inner(self.operations) else {
-            tracing::error!("commiting transaction with contexts yet in use");
+        let Some((ops, resource_ops)) =
+            into_inner(self.operations).zip(into_inner(self.resource_operations))
+        else {
             return;
         };
 
-        let ops = match ops.into_inner() {
-            Ok(ops) => ops,
-            Err(err) => {
-                tracing::error!(error = err.to_string(), "committing poisoned transaction");
+        let (mut upstream_ops, mut upstream_resource_ops) = match (
+            self.context.operations.write(),
+            self.context.resource_operations.write(),
+        ) {
+            (Ok(ops), Ok(resource_ops)) => (ops, resource_ops),
+            (Err(err), _) => {
+                tracing::error!(
+                    error = err.to_string(),
+                    "committing transaction into poisoned context"
+                );
                 return;
             }
-        };
-
-        let mut upstream_ops = match self.context.operations.write() {
-            Ok(ops) => ops,
-            Err(err) => {
+            (_, Err(err)) => {
                 tracing::error!(
                     error = err.to_string(),
                     "committing transaction into poisoned context"
@@ -163,11 +173,36 @@ where
         };
 
         upstream_ops.extend(ops);
+        upstream_resource_ops.extend(resource_ops);
+    }
+}
+
+/// Takes the operations of a transaction out of their lock, if no context is using them anymore.
+fn into_inner<O>(operations: Arc<RwLock<Vec<O>>>) -> Option<Vec<O>> {
+    let Some(ops) = Arc::into_inner(operations) else {
+        tracing::error!("commiting transaction with contexts yet in use");
+        return None;
+    };
+
+    match ops.into_inner() {
+        Ok(ops) => Some(ops),
+        Err(err) => {
+            tracing::error!(error = err.to_string(), "committing poisoned transaction");
+            None
+        }
     }
 }
 
 /// Represents an operation into the schema.
-enum Operation<T>
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "T: serde::Serialize, T::Id: serde::Serialize",
+        deserialize = "T: serde::Deserialize<'de>, T::Id: serde::Deserialize<'de>"
+    ))
+)]
+pub enum Operation<T>
 where
     T: Identify,
 {
@@ -175,6 +210,32 @@ where
     Delete(T::Id),
 }
 
+impl<T> Clone for Operation<T>
+where
+    T: Identify + Clone,
+    T::Id: Clone,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Operation::Save(node) => Operation::Save(node.clone()),
+            Operation::Delete(node_id) => Operation::Delete(node_id.clone()),
+        }
+    }
+}
+
+impl<T> Debug for Operation<T>
+where
+    T: Identify + Debug,
+    T::Id: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operation::Save(node) => f.debug_tuple("Save").field(node).finish(),
+            Operation::Delete(node_id) => f.debug_tuple("Delete").field(node_id).finish(),
+        }
+    }
+}
+
 impl<T> Identify for Operation<T>
 where
     T: Identify,
@@ -189,6 +250,27 @@ where
     }
 }
 
+impl<T> Operation<T>
+where
+    T: Identify,
+{
+    /// Returns the kind of this operation.
+    pub fn kind(&self) -> OperationKind {
+        match self {
+            Operation::Save(_) => OperationKind::Save,
+            Operation::Delete(_) => OperationKind::Delete,
+        }
+    }
+}
+
+/// The kind of an [`Operation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OperationKind {
+    Save,
+    Delete,
+}
+
 /// The node targeted by a context.
 pub struct Target<T> {
     lock: Option<Arc<RwLock<T>>>,
@@ -357,6 +439,7 @@ where
     schema: &'a Schema<T>,
     parent: Option<&'a Context<'a, T>>,
     operations: Arc<RwLock<Vec<Operation<T>>>>,
+    resource_operations: Arc<RwLock<Vec<ResourceOperation>>>,
     target: Target<T>,
 }
 
@@ -367,14 +450,14 @@ where
 {
     type Node = T;
 
-    fn get(&self, id: &<Self::Node as Identify>::Id) -> Option<Self::Node> {
+    fn get(&self, id: &<Self::Node as Identify>::Id) -> Option<Cow<'_, Self::Node>> {
         let guard = match self.operations.read() {
             Ok(ops) => ops,
             Err(err) => err.into_inner(),
         };
 
         match guard.iter().rev().find(|&op| op.id() == id) {
-            Some(Operation::Save(node)) => Some(node.clone()),
+            Some(Operation::Save(node)) => Some(Cow::Owned(node.clone())),
             Some(Operation::Delete(_)) => None,
             None => self
                 .parent
@@ -409,6 +492,7 @@ where
             schema: tx.schema,
             graph: tx.guard.get_or_init(|| tx.schema.write()),
             operations: tx.operations.clone(),
+            resource_operations: tx.resource_operations.clone(),
             target: Default::default(),
             parent: Default::default(),
         }
@@ -424,6 +508,7 @@ where
             graph: tx.context.graph,
             schema: tx.context.schema,
             operations: tx.operations.clone(),
+            resource_operations: tx.resource_operations.clone(),
             target: Default::default(),
             parent: Some(tx.context),
         }
@@ -471,6 +556,48 @@ where
         guard.push(Operation::Delete(node_id));
     }
 
+    /// Registers the insertion of the given resource as part of the transaction.
+    ///
+    /// If the resource already exists, the old value is overwritten once the transaction is
+    /// committed.
+    pub fn insert_resource<R>(&self, resource: R)
+    where
+        R: 'static + Send + Sync,
+    {
+        let mut guard = match self.resource_operations.write() {
+            Ok(ops) => ops,
+            Err(err) => err.into_inner(),
+        };
+
+        guard.push(ResourceOperation::insert(resource));
+    }
+
+    /// Registers the removal of the resource of type R as part of the transaction.
+    pub fn remove_resource<R>(&self)
+    where
+        R: 'static,
+    {
+        let mut guard = match self.resource_operations.write() {
+            Ok(ops) => ops,
+            Err(err) => err.into_inner(),
+        };
+
+        guard.push(ResourceOperation::remove::<R>());
+    }
+
+    /// Returns the resource of type R from the underlying schema.
+    ///
+    /// If the resource does not exist but the schema knows how to initialize it, it is built from
+    /// the graph as it was before the transaction began.
+    pub fn resource<R>(&self) -> Res<R>
+    where
+        R: 'static,
+    {
+        let mut res = Res::from(self.resources());
+        self.schema.initializers.initialize(&mut res, || self.graph);
+        res
+    }
+
     /// Returns a reference to the underlying schema's [`ResourceSet`].
     pub fn resources(&self) -> &ResourceSet {
         self.schema.resources()
@@ -510,6 +637,24 @@ where
     pub fn transaction(&'a self) -> Foreground<'a, T> {
         self.context.into()
     }
+
+    /// Registers the insertion of the given resource as part of the transaction.
+    #[inline]
+    pub fn insert_resource<R>(&self, resource: R)
+    where
+        R: 'static + Send + Sync,
+    {
+        self.context.insert_resource(resource)
+    }
+
+    /// Registers the removal of the resource of type R as part of the transaction.
+    #[inline]
+    pub fn remove_resource<R>(&self)
+    where
+        R: 'static,
+    {
+        self.context.remove_resource::<R>()
+    }
 }
 
 impl<'a, T> From<&'a Context<'a, T>> for Ctx<'a, T>
@@ -2,6 +2,9 @@
 
 use std::{any::TypeId, collections::BTreeMap, marker::PhantomData};
 
+#[cfg(feature = "macros")]
+pub use plotline_macros::trigger;
+
 use crate::id::Identify;
 
 use super::{
@@ -48,7 +51,7 @@ impl_trigger!(A, B, C, D, E, F, G, H);
 
 /// Implements the [`Trigger`] trait for a selection of triggers.
 pub struct TriggerSelect<'a, T> {
-    triggers: Option<&'a [Box<dyn Trigger<T, ()>>]>,
+    triggers: Option<&'a [Box<dyn Trigger<T, ()> + Send + Sync>]>,
 }
 
 impl<I> Default for TriggerSelect<'_, I> {
@@ -74,7 +77,7 @@ where
 
 /// A set of arbitrary triggers.
 pub struct TriggerSet<T> {
-    triggers: BTreeMap<TypeId, Vec<Box<dyn Trigger<T, ()>>>>,
+    triggers: BTreeMap<TypeId, Vec<Box<dyn Trigger<T, ()> + Send + Sync>>>,
     _node: PhantomData<T>,
 }
 
@@ -92,13 +95,18 @@ where
     T: Identify,
 {
     /// Schedules a new trigger.
-    pub fn with_trigger<S, Args>(mut self, _: S, trigger: impl Trigger<T, Args> + 'static) -> Self
+    pub fn with_trigger<S, Args>(
+        mut self,
+        _: S,
+        trigger: impl Trigger<T, Args> + Send + Sync + 'static,
+    ) -> Self
     where
         T: 'static,
         S: 'static,
         Args: 'static,
     {
-        let trigger: Box<dyn Trigger<T, ()>> = Box::new(ArglessTrigger::from(trigger));
+        let trigger: Box<dyn Trigger<T, ()> + Send + Sync> =
+            Box::new(ArglessTrigger::from(trigger));
         let scheduler = TypeId::of::<S>();
 
         match self.triggers.get_mut(&scheduler) {
@@ -111,6 +119,14 @@ where
         self
     }
 
+    /// Removes all the triggers scheduled for the given type, returning them.
+    pub(crate) fn remove<S>(&mut self, _: S) -> Vec<Box<dyn Trigger<T, ()> + Send + Sync>>
+    where
+        S: 'static,
+    {
+        self.triggers.remove(&TypeId::of::<S>()).unwrap_or_default()
+    }
+
     /// Returns an iterator over the triggers scheduled for the given type.
     pub fn select<S>(&self, _: S) -> TriggerSelect<'_, T>
     where
@@ -129,7 +145,9 @@ where
 /// Wraps a trigger into an argless implementation of [`Trigger`].
 struct ArglessTrigger<T, M> {
     trigger: T,
-    _meta: PhantomData<M>,
+    // The arguments are never owned by the wrapper, so they must not affect its
+    // thread-safety.
+    _meta: PhantomData<fn() -> M>,
 }
 
 impl<T, M> From<T> for ArglessTrigger<T, M> {
@@ -230,4 +248,57 @@ mod tests {
             "only scheduled triggers should be executed"
         );
     }
+
+    #[cfg(feature = "macros")]
+    #[test]
+    fn trigger_attribute_should_schedule_function() {
+        use crate::{
+            graph::fixtures::{fake_node, FakeNode},
+            schema::{ops::save::Save, plugin::Plugin},
+        };
+
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        struct Schedule1;
+        struct Schedule2;
+
+        #[super::trigger(Schedule1, Schedule2)]
+        fn count(_: Ctx<FakeNode<'static, usize>>) -> Result<()> {
+            COUNT.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+
+        assert!(
+            count.name().ends_with("::count"),
+            "trigger name should be its path"
+        );
+
+        let schema = Schema::from(Graph::default().with_node(fake_node!(1)))
+            .install(count)
+            .expect("trigger should be installed");
+
+        schema
+            .transaction()
+            .with(|ctx| {
+                schema.triggers().select(Schedule1).execute(&ctx)?;
+                schema.triggers().select(Schedule2).execute(&ctx)
+            })
+            .expect("transaction should not fail");
+
+        assert_eq!(
+            COUNT.load(Ordering::Relaxed),
+            2,
+            "trigger should be scheduled for all the given schedulers"
+        );
+
+        Save::new(fake_node!(2))
+            .execute(schema.transaction())
+            .expect("save transaction should not fail");
+
+        assert_eq!(
+            COUNT.load(Ordering::Relaxed),
+            2,
+            "trigger should not be scheduled for other schedulers"
+        );
+    }
 }
@@ -0,0 +1,251 @@
+//! The index definition.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::{Bound, RangeBounds},
+};
+
+use plotline::{id::Identify, prefix::Prefixed};
+
+/// Maps the values of a property to the ids of the nodes holding them.
+pub struct Index<T, P>
+where
+    T: Identify,
+{
+    /// The ids of the nodes holding each value.
+    ids: BTreeMap<P, BTreeSet<T::Id>>,
+    /// The values held by each node.
+    values: BTreeMap<T::Id, Vec<P>>,
+}
+
+impl<T, P> Default for Index<T, P>
+where
+    T: Identify,
+{
+    fn default() -> Self {
+        Self {
+            ids: Default::default(),
+            values: Default::default(),
+        }
+    }
+}
+
+impl<T, P> Index<T, P>
+where
+    T: Identify,
+    T::Id: Ord + Clone,
+    P: Ord + Clone,
+{
+    /// Sets the given values for the node with the given id, replacing any previous ones.
+    pub fn insert(&mut self, node_id: T::Id, values: Vec<P>) {
+        self.remove(&node_id);
+        if values.is_empty() {
+            return;
+        }
+
+        values.iter().for_each(|value| {
+            self.ids
+                .entry(value.clone())
+                .or_default()
+                .insert(node_id.clone());
+        });
+
+        self.values.insert(node_id, values);
+    }
+
+    /// Removes all the values of the node with the given id.
+    pub fn remove(&mut self, node_id: &T::Id) {
+        let Some(values) = self.values.remove(node_id) else {
+            return;
+        };
+
+        values.into_iter().for_each(|value| {
+            let Some(ids) = self.ids.get_mut(&value) else {
+                return;
+            };
+
+            ids.remove(node_id);
+            if ids.is_empty() {
+                self.ids.remove(&value);
+            }
+        });
+    }
+}
+
+impl<T, P> Index<T, P>
+where
+    T: Identify,
+    T::Id: Ord,
+    P: Ord,
+{
+    /// Returns the ids of all the nodes holding the given value.
+    pub fn get(&self, value: &P) -> impl Iterator<Item = &T::Id> {
+        self.ids.get(value).into_iter().flatten()
+    }
+
+    /// Returns all the values in the given range, together with the id of each node holding
+    /// them, in order.
+    pub fn range<R>(&self, range: R) -> impl Iterator<Item = (&P, &T::Id)>
+    where
+        R: RangeBounds<P>,
+    {
+        self.ids
+            .range(range)
+            .flat_map(|(value, ids)| ids.iter().map(move |id| (value, id)))
+    }
+
+    /// Returns all the values starting with the given prefix, together with the id of each node
+    /// holding them, in order.
+    pub fn prefix<'a>(&'a self, prefix: &'a P) -> impl Iterator<Item = (&'a P, &'a T::Id)>
+    where
+        P: Prefixed,
+    {
+        self.range((Bound::Included(prefix), Bound::Unbounded))
+            .take_while(|(value, _)| value.starts_with(prefix))
+    }
+
+    /// Returns the values held by the node with the given id.
+    pub fn values(&self, node_id: &T::Id) -> &[P] {
+        self.values
+            .get(node_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns an iterator over all the distinct values in the index, together with the amount
+    /// of nodes holding each of them.
+    pub fn counts(&self) -> impl Iterator<Item = (&P, usize)> {
+        self.ids.iter().map(|(value, ids)| (value, ids.len()))
+    }
+
+    /// Returns the amount of distinct values in the index.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns true if, and only if, the index has no values.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
+
+/// Indexes are serialized as the values of each node, since the ids holding each value can be
+/// derived from them.
+#[cfg(feature = "serde")]
+impl<T, P> serde::Serialize for Index<T, P>
+where
+    T: Identify,
+    T::Id: serde::Serialize,
+    P: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.values.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, P> serde::Deserialize<'de> for Index<T, P>
+where
+    T: Identify,
+    T::Id: Ord + Clone + serde::Deserialize<'de>,
+    P: Ord + Clone + serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let values = BTreeMap::<T::Id, Vec<P>>::deserialize(deserializer)?;
+        Ok(values
+            .into_iter()
+            .fold(Self::default(), |mut index, (node_id, values)| {
+                index.insert(node_id, values);
+                index
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plotline::id::Identify;
+
+    use super::Index;
+
+    struct Node(usize);
+
+    impl Identify for Node {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.0
+        }
+    }
+
+    fn index() -> Index<Node, String> {
+        let mut index = Index::default();
+        index.insert(1, vec!["character".into(), "character/main".into()]);
+        index.insert(2, vec!["character/secondary".into()]);
+        index.insert(3, vec!["place".into()]);
+        index
+    }
+
+    #[test]
+    fn exact_lookup() {
+        let index = index();
+
+        assert_eq!(index.get(&"character".into()).collect::<Vec<_>>(), vec![&1]);
+
+        assert_eq!(
+            index.get(&"chapter".into()).count(),
+            0,
+            "missing values should hold no ids"
+        );
+    }
+
+    #[test]
+    fn prefix_lookup() {
+        let index = index();
+        let prefix = "character/".to_string();
+
+        assert_eq!(
+            index.prefix(&prefix).map(|(_, id)| *id).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn range_lookup() {
+        let index = index();
+
+        assert_eq!(
+            index
+                .range("character/main".to_string()..="place".to_string())
+                .map(|(_, id)| *id)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn inserting_should_replace_previous_values() {
+        let mut index = index();
+        index.insert(1, vec!["place".into()]);
+
+        assert_eq!(index.get(&"character".into()).count(), 0);
+        assert_eq!(index.get(&"place".into()).collect::<Vec<_>>(), vec![&1, &3]);
+
+        assert_eq!(index.values(&1), &["place".to_string()]);
+    }
+
+    #[test]
+    fn removing_should_drop_empty_values() {
+        let mut index = index();
+        index.remove(&3);
+
+        assert_eq!(index.get(&"place".into()).count(), 0);
+        assert_eq!(index.len(), 3, "values with no ids should be removed");
+        assert!(index.values(&3).is_empty());
+    }
+}
@@ -0,0 +1,6 @@
+//! A secondary index over the values of a property.
+
+mod index;
+mod plugin;
+pub use index::Index;
+pub use plugin::IndexPlugin;
@@ -0,0 +1,228 @@
+//! The plugin implementation for [`Index`].
+
+use plotline::prelude::*;
+
+use crate::Index;
+
+/// Implements the [`Plugin`] trait for an index over the values of the property P in nodes of
+/// type T.
+///
+/// The index is available as a `Res<Index<T, P>>` resource. Plugins built from an existing
+/// index start from it instead of an empty one.
+///
+/// The plugin is named `index` unless told otherwise, so installing more than one index in the
+/// same schema requires naming them apart.
+pub struct IndexPlugin<T, P>
+where
+    T: Identify,
+{
+    name: &'static str,
+    index: Index<T, P>,
+}
+
+impl<T, P> Default for IndexPlugin<T, P>
+where
+    T: Identify,
+{
+    fn default() -> Self {
+        Self {
+            name: "index",
+            index: Default::default(),
+        }
+    }
+}
+
+impl<T, P> From<Index<T, P>> for IndexPlugin<T, P>
+where
+    T: Identify,
+{
+    fn from(index: Index<T, P>) -> Self {
+        Self {
+            index,
+            ..Default::default()
+        }
+    }
+}
+
+impl<T, P> IndexPlugin<T, P>
+where
+    T: Identify,
+{
+    /// Sets the name the plugin is installed with.
+    pub fn with_name(mut self, name: &'static str) -> Self {
+        self.name = name;
+        self
+    }
+}
+
+impl<T, P> IndexPlugin<T, P>
+where
+    T: 'static + Identify + Send + Sync,
+    T::Id: Ord + Clone + Send + Sync,
+    P: 'static + Property<T> + Ord + Clone + Send + Sync,
+{
+    fn on_save(_: Ctx<T>, target: Target<T>, index: Res<Index<T, P>>) -> Result<()> {
+        let Some((node_id, values)) = target.with(|node| (node.id().clone(), P::all(node))) else {
+            return Ok(());
+        };
+
+        index.with_mut(|index| index.insert(node_id, values));
+        Ok(())
+    }
+
+    fn on_delete(_: Ctx<T>, target: Target<T>, index: Res<Index<T, P>>) -> Result<()> {
+        let Some(node_id) = target.with(|node| node.id().clone()) else {
+            return Ok(());
+        };
+
+        index.with_mut(|index| index.remove(&node_id));
+        Ok(())
+    }
+}
+
+impl<T, P> Plugin<T> for IndexPlugin<T, P>
+where
+    T: 'static + Identify + Send + Sync,
+    T::Id: Ord + Clone + Send + Sync,
+    P: 'static + Property<T> + Ord + Clone + Send + Sync,
+{
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    fn install(self, schema: Schema<T>) -> Schema<T>
+    where
+        T: Identify,
+    {
+        schema
+            .with_resource(self.index)
+            .with_trigger(OnInstall, Self::on_save)
+            .with_trigger(AfterSave, Self::on_save)
+            .with_trigger(AfterDelete, Self::on_delete)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plotline::{
+        graph::Graph,
+        prelude::*,
+        schema::ops::{delete::Delete, save::Save},
+    };
+
+    use crate::{Index, IndexPlugin};
+
+    #[derive(Debug, Clone)]
+    struct Node {
+        id: usize,
+        status: &'static str,
+    }
+
+    impl Identify for Node {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.id
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+    struct Status(&'static str);
+
+    impl Property<Node> for Status {
+        fn all(source: &Node) -> Vec<Self> {
+            vec![Status(source.status)]
+        }
+    }
+
+    fn drafts(schema: &Schema<Node>) -> Vec<usize> {
+        Res::<Index<Node, Status>>::from(schema.resources())
+            .with(|index| index.get(&Status("draft")).copied().collect())
+            .expect("index should exist")
+    }
+
+    #[test]
+    fn index_should_follow_schema_changes() {
+        let schema = Schema::from(Graph::from_iter([
+            Node {
+                id: 1,
+                status: "draft",
+            },
+            Node {
+                id: 2,
+                status: "done",
+            },
+        ]))
+        .install(IndexPlugin::<Node, Status>::default())
+        .expect("plugin should be installed");
+
+        assert_eq!(drafts(&schema), vec![1], "existing nodes should be indexed");
+
+        Save::new(Node {
+            id: 2,
+            status: "draft",
+        })
+        .execute(schema.transaction())
+        .expect("save transaction should not fail");
+
+        assert_eq!(drafts(&schema), vec![1, 2], "saved nodes should be indexed");
+
+        Save::new(Node {
+            id: 1,
+            status: "done",
+        })
+        .execute(schema.transaction())
+        .expect("save transaction should not fail");
+
+        assert_eq!(drafts(&schema), vec![2], "old values should be removed");
+
+        Delete::new(2)
+            .execute(schema.transaction())
+            .expect("delete transaction should not fail");
+
+        assert!(
+            drafts(&schema).is_empty(),
+            "deleted nodes should be removed"
+        );
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+    struct Parity(bool);
+
+    impl Property<Node> for Parity {
+        fn all(source: &Node) -> Vec<Self> {
+            vec![Parity(source.id.is_multiple_of(2))]
+        }
+    }
+
+    #[test]
+    fn indexes_should_be_named_apart() {
+        let schema = Schema::from(Graph::<Node>::default())
+            .install(IndexPlugin::<Node, Status>::default())
+            .expect("plugin should be installed");
+
+        assert!(schema.plugins().contains("index"));
+
+        let result = schema.install(IndexPlugin::<Node, Parity>::default());
+        assert!(
+            matches!(result, Err(Error::DuplicatedPlugin("index"))),
+            "indexes with the same name should be rejected"
+        );
+
+        let schema = Schema::from(Graph::<Node>::default())
+            .install(IndexPlugin::<Node, Status>::default().with_name("status"))
+            .and_then(|schema| schema.install(IndexPlugin::<Node, Parity>::default()))
+            .expect("plugins should be installed");
+
+        let names: Vec<_> = schema
+            .plugins()
+            .into_iter()
+            .map(|plugin| plugin.name)
+            .collect();
+        assert_eq!(names, vec!["status", "index"]);
+    }
+}
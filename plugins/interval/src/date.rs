@@ -27,6 +27,15 @@ pub struct Date<const N: usize, T, Endian> {
     endian: PhantomData<Endian>,
 }
 
+impl<const N: usize, T, Endian> From<[T; N]> for Date<N, T, Endian> {
+    fn from(components: [T; N]) -> Self {
+        Self {
+            components,
+            endian: PhantomData,
+        }
+    }
+}
+
 impl<const N: usize, T, Endian> Eq for Date<N, T, Endian> where T: Eq {}
 
 impl<const N: usize, T, Endian> PartialEq for Date<N, T, Endian>
@@ -47,6 +56,41 @@ where
     }
 }
 
+/// Dates are serialized as the sequence of their components.
+#[cfg(feature = "serde")]
+impl<const N: usize, T, Endian> serde::Serialize for Date<N, T, Endian>
+where
+    T: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(&self.components)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize, T, Endian> serde::Deserialize<'de> for Date<N, T, Endian>
+where
+    T: serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let components = Vec::<T>::deserialize(deserializer)?;
+        let len = components.len();
+
+        Ok(Self {
+            components: components.try_into().map_err(|_| {
+                serde::de::Error::invalid_length(len, &format!("{N} components").as_str())
+            })?,
+            endian: PhantomData,
+        })
+    }
+}
+
 impl<const N: usize, T, Endian> Interval for Date<N, T, Endian>
 where
     Self: Copy + Ord,
@@ -106,4 +150,31 @@ where
 //     fn all(&self, source: &Src) -> Vec<Self::Target> {
 //         todo!()
 //     }
-// }
\ No newline at end of file
+// }
+#[cfg(test)]
+mod tests {
+    use super::{BigEndianDate, LittleEndianDate};
+
+    #[test]
+    fn dates_should_be_ordered_by_endian() {
+        assert!(BigEndianDate::from([2024, 1, 31]) < BigEndianDate::from([2024, 2, 1]));
+        assert!(LittleEndianDate::from([31, 1, 2024]) < LittleEndianDate::from([1, 2, 2024]));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialized_date_should_match_the_original() {
+        let original = BigEndianDate::from([2024, 2, 1]);
+        let json = serde_json::to_string(&original).expect("date should serialize");
+        assert_eq!(json, "[2024,2,1]");
+
+        let date: BigEndianDate<3, u16> =
+            serde_json::from_str(&json).expect("date should deserialize");
+        assert_eq!(date, original);
+
+        assert!(
+            serde_json::from_str::<BigEndianDate<3, u16>>("[2024,2]").is_err(),
+            "dates with the wrong amount of components should not deserialize"
+        );
+    }
+}
@@ -5,6 +5,7 @@ pub mod date;
 mod node;
 mod plugin;
 mod tree;
+pub use plugin::IntervalPlugin;
 pub use tree::IntervalSearchTree;
 
 /// One of the limits in an [`Interval`].
@@ -49,7 +50,7 @@ pub mod fixtures {
     use super::{Bound, Interval};
 
     /// A mock implementation for the [`Interval`] trait.
-    #[derive(Default, Clone, PartialOrd, Ord)]
+    #[derive(Default, Clone)]
     pub struct IntervalMock<Bound> {
         lo_fn: Option<fn() -> Bound>,
         hi_fn: Option<fn() -> Bound>,
@@ -1,8 +1,6 @@
 //! The plugin implementation for [`IntervalSearchTree`].
 
-use std::marker::PhantomData;
-
-use plotline::{prelude::*, property::Extractor};
+use plotline::prelude::*;
 
 use crate::{Interval, IntervalSearchTree};
 
@@ -48,75 +46,77 @@ impl<Id, Intv> Identify for NodeInterval<Id, Intv> {
 
 type SearchTree<Id, Intv> = IntervalSearchTree<NodeInterval<Id, Intv>>;
 
-/// Implements the [`Plugin`] trait for an arbitrary extractor of intervals from a source of type T.
-pub struct IntervalPlugin<T, Extractor> {
-    extractor: Extractor,
-    node: PhantomData<T>,
+/// Implements the [`Plugin`] trait for an interval search tree over the intervals of type Intv
+/// in nodes of type T.
+pub struct IntervalPlugin<T, Intv>
+where
+    T: Identify,
+    Intv: Interval,
+{
+    search_tree: SearchTree<T::Id, Intv>,
 }
 
-impl<T, Extr> IntervalPlugin<T, Extr>
+impl<T, Intv> Default for IntervalPlugin<T, Intv>
 where
-    T: 'static + Identify,
-    T::Id: Clone + PartialEq,
-    Extr: 'static + Extractor<T>,
-    Extr::Target: Interval + PartialEq,
+    T: Identify,
+    Intv: Interval,
 {
-    fn on_delete(
+    fn default() -> Self {
+        Self {
+            search_tree: Default::default(),
+        }
+    }
+}
+
+impl<T, Intv> IntervalPlugin<T, Intv>
+where
+    T: 'static + Identify + Send + Sync,
+    T::Id: Clone + PartialEq + Send + Sync,
+    Intv: 'static + Property<T> + Interval + PartialEq + Send + Sync,
+    Intv::Bound: Send + Sync,
+{
+    fn intervals(target: Target<T>) -> Option<Vec<NodeInterval<T::Id, Intv>>> {
+        target.with(|node| {
+            Intv::all(node)
+                .into_iter()
+                .map(|interval| NodeInterval {
+                    node_id: node.id().clone(),
+                    interval,
+                })
+                .collect()
+        })
+    }
+
+    fn on_save(
         _: Ctx<T>,
         target: Target<T>,
-        search_tree: Res<SearchTree<T::Id, Extr::Target>>,
-        extractor: Res<Extr>,
+        search_tree: Res<SearchTree<T::Id, Intv>>,
     ) -> Result<()> {
-        let Some(intervals) = (target, extractor).with(|(target, factory)| {
-            factory.all(target).into_iter().map({
-                let node_id = target.id().clone();
-                move |interval| NodeInterval {
-                    node_id: node_id.clone(),
-                    interval,
-                }
-            })
-        }) else {
+        let Some(intervals) = Self::intervals(target) else {
             return Ok(());
         };
 
         search_tree.with_mut(|search_tree| {
             intervals.into_iter().for_each(|interval| {
-                search_tree.delete(&interval);
+                search_tree.insert(interval);
             });
         });
 
         Ok(())
     }
-}
 
-impl<T, Extr> IntervalPlugin<T, Extr>
-where
-    T: 'static + Identify,
-    T::Id: Clone + PartialEq,
-    Extr: 'static + Extractor<T>,
-    Extr::Target: Interval + PartialEq,
-{
-    fn on_save(
+    fn on_delete(
         _: Ctx<T>,
         target: Target<T>,
-        search_tree: Res<SearchTree<T::Id, Extr::Target>>,
-        extractor: Res<Extr>,
+        search_tree: Res<SearchTree<T::Id, Intv>>,
     ) -> Result<()> {
-        let Some(intervals) = (target, extractor).with(|(target, extractor)| {
-            extractor.all(target).into_iter().map({
-                let node_id = target.id().clone();
-                move |interval| NodeInterval {
-                    node_id: node_id.clone(),
-                    interval,
-                }
-            })
-        }) else {
+        let Some(intervals) = Self::intervals(target) else {
             return Ok(());
         };
 
         search_tree.with_mut(|search_tree| {
-            intervals.into_iter().for_each(|interval| {
-                search_tree.insert(interval);
+            intervals.iter().for_each(|interval| {
+                search_tree.delete(interval);
             });
         });
 
@@ -124,21 +124,141 @@ where
     }
 }
 
-impl<T, Extr> Plugin<T> for IntervalPlugin<T, Extr>
+impl<T, Intv> Plugin<T> for IntervalPlugin<T, Intv>
 where
-    T: 'static + Identify,
-    T::Id: Clone + PartialEq,
-    Extr: 'static + Extractor<T>,
-    Extr::Target: Interval + PartialEq,
+    T: 'static + Identify + Send + Sync,
+    T::Id: Clone + PartialEq + Send + Sync,
+    Intv: 'static + Property<T> + Interval + PartialEq + Send + Sync,
+    Intv::Bound: Send + Sync,
 {
+    fn name(&self) -> &'static str {
+        "interval"
+    }
+
+    fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
     fn install(self, schema: Schema<T>) -> Schema<T>
     where
         T: Identify,
     {
         schema
-            .with_resource(self.extractor)
-            .with_resource(SearchTree::<T, Extr::Target>::default())
+            .with_resource(self.search_tree)
+            .with_trigger(OnInstall, Self::on_save)
             .with_trigger(AfterSave, Self::on_save)
             .with_trigger(AfterDelete, Self::on_delete)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use plotline::{
+        graph::Graph,
+        prelude::*,
+        schema::ops::{delete::Delete, save::Save},
+    };
+
+    use crate::{Interval, IntervalPlugin};
+
+    use super::{NodeInterval, SearchTree};
+
+    #[derive(Debug, Clone)]
+    struct Node {
+        id: usize,
+        span: (usize, usize),
+    }
+
+    impl Identify for Node {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.id
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Span(usize, usize);
+
+    impl Interval for Span {
+        type Bound = usize;
+
+        fn lo(&self) -> Self::Bound {
+            self.0
+        }
+
+        fn hi(&self) -> Self::Bound {
+            self.1
+        }
+    }
+
+    impl Property<Node> for Span {
+        fn all(source: &Node) -> Vec<Self> {
+            vec![Span(source.span.0, source.span.1)]
+        }
+    }
+
+    fn overlapping(schema: &Schema<Node>, span: Span) -> Vec<usize> {
+        let mut ids = Res::<SearchTree<usize, Span>>::from(schema.resources())
+            .with(|tree| {
+                let mut ids = Vec::default();
+                tree.for_each_intersection(
+                    &NodeInterval {
+                        node_id: 0,
+                        interval: span,
+                    },
+                    |interval| ids.push(*interval.id()),
+                );
+                ids
+            })
+            .expect("search tree should exist");
+
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn search_tree_should_follow_schema_changes() {
+        let schema = Schema::from(Graph::from_iter([
+            Node {
+                id: 1,
+                span: (0, 2),
+            },
+            Node {
+                id: 2,
+                span: (5, 9),
+            },
+        ]))
+        .install(IntervalPlugin::<Node, Span>::default())
+        .expect("plugin should be installed");
+
+        assert_eq!(
+            overlapping(&schema, Span(1, 6)),
+            vec![1, 2],
+            "existing nodes should be indexed"
+        );
+
+        Save::new(Node {
+            id: 3,
+            span: (3, 4),
+        })
+        .execute(schema.transaction())
+        .expect("save transaction should not fail");
+
+        assert_eq!(
+            overlapping(&schema, Span(3, 3)),
+            vec![3],
+            "saved nodes should be indexed"
+        );
+
+        Delete::new(2)
+            .execute(schema.transaction())
+            .expect("delete transaction should not fail");
+
+        assert_eq!(
+            overlapping(&schema, Span(1, 6)),
+            vec![1, 3],
+            "deleted nodes should be removed"
+        );
+    }
+}
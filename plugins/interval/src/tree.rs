@@ -21,6 +21,50 @@ where
     }
 }
 
+impl<Intv> FromIterator<Intv> for IntervalSearchTree<Intv>
+where
+    Intv: Interval,
+{
+    fn from_iter<I: IntoIterator<Item = Intv>>(iter: I) -> Self {
+        let mut tree = Self::default();
+        iter.into_iter().for_each(|interval| tree.insert(interval));
+        tree
+    }
+}
+
+/// Trees are serialized as the sequence of their intervals, each node before its children, so
+/// inserting them back in order preserves the shape of the tree.
+#[cfg(feature = "serde")]
+impl<Intv> serde::Serialize for IntervalSearchTree<Intv>
+where
+    Intv: Interval + serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut intervals = Vec::new();
+        if let Some(root) = &self.root {
+            root.for_each_preorder(|interval| intervals.push(interval));
+        }
+
+        serializer.collect_seq(intervals)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, Intv> serde::Deserialize<'de> for IntervalSearchTree<Intv>
+where
+    Intv: Interval + serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Vec::<Intv>::deserialize(deserializer).map(Self::from_iter)
+    }
+}
+
 impl<Intv> IntervalSearchTree<Intv>
 where
     Intv: PartialEq + Interval,
@@ -67,9 +111,9 @@ where
     where
         F: FnMut(&Intv),
     {
-        self.root
-            .as_ref()
-            .map(|root| root.for_each_intersection(interval, f));
+        if let Some(root) = &self.root {
+            root.for_each_intersection(interval, f);
+        }
     }
 }
 
@@ -200,4 +244,63 @@ mod tests {
             });
         })
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialized_tree_should_match_the_original() {
+        use crate::Interval;
+
+        #[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Span(usize, usize);
+
+        impl Interval for Span {
+            type Bound = usize;
+
+            fn lo(&self) -> Self::Bound {
+                self.0
+            }
+
+            fn hi(&self) -> Self::Bound {
+                self.1
+            }
+        }
+
+        let original = IntervalSearchTree::default()
+            .with_interval(Span(5, 6))
+            .with_interval(Span(0, 2))
+            .with_interval(Span(3, 3))
+            .with_interval(Span(5, 9))
+            .with_interval(Span(6, 6));
+
+        let json = serde_json::to_string(&original).expect("tree should serialize");
+        let tree: IntervalSearchTree<Span> =
+            serde_json::from_str(&json).expect("tree should deserialize");
+
+        assert_eq!(
+            serde_json::to_string(&tree).expect("tree should serialize"),
+            json,
+            "the shape of the tree should be preserved"
+        );
+
+        let mut intervals = Vec::default();
+        tree.for_each_intersection(&Span(3, 5), |interval| intervals.push(*interval));
+        intervals.sort_by_key(|interval| (interval.0, interval.1));
+        assert_eq!(intervals, vec![Span(3, 3), Span(5, 6), Span(5, 9)]);
+
+        // The bounds cached by the nodes are never read from the input, so they cannot be
+        // corrupted: (5, 9) would be missed if the max of the left subtree was trusted.
+        let corrupted = concat!(
+            r#"{"value":[5,6],"max":6,"left":"#,
+            r#"{"value":[0,9],"max":2,"left":null,"right":null},"#,
+            r#""right":null}"#
+        );
+        assert!(
+            serde_json::from_str::<IntervalSearchTree<Span>>(corrupted).is_err(),
+            "trees should not be deserialized from their nodes"
+        );
+
+        let tree: IntervalSearchTree<Span> =
+            serde_json::from_str("[[5,6],[0,9]]").expect("tree should deserialize");
+        assert!(tree.intersects(&Span(8, 8)), "max bounds should be rebuilt");
+    }
 }
@@ -0,0 +1,408 @@
+//! The inverted index definition.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    marker::PhantomData,
+};
+
+use plotline::id::Identify;
+
+use crate::{
+    query::{Clause, Query},
+    token::tokenize,
+};
+
+/// The term frequency saturation parameter of BM25.
+const K1: f64 = 1.2;
+/// The length normalization parameter of BM25.
+const B: f64 = 0.75;
+
+/// A node matching a query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hit<'a, Id> {
+    /// The id of the matching node.
+    pub id: &'a Id,
+    /// The relevance of the node for the query.
+    pub score: f64,
+}
+
+/// Maps the terms in the texts of the property P to the nodes holding them.
+pub struct SearchIndex<T, P>
+where
+    T: Identify,
+{
+    /// The positions of each term in each node.
+    postings: BTreeMap<String, BTreeMap<T::Id, Vec<usize>>>,
+    /// The distinct terms of each node.
+    terms: BTreeMap<T::Id, BTreeSet<String>>,
+    /// The amount of terms in each node.
+    lengths: BTreeMap<T::Id, usize>,
+    /// The sum of the lengths of all nodes.
+    total_length: usize,
+    _property: PhantomData<fn() -> P>,
+}
+
+impl<T, P> Default for SearchIndex<T, P>
+where
+    T: Identify,
+{
+    fn default() -> Self {
+        Self {
+            postings: Default::default(),
+            terms: Default::default(),
+            lengths: Default::default(),
+            total_length: 0,
+            _property: PhantomData,
+        }
+    }
+}
+
+impl<T, P> SearchIndex<T, P>
+where
+    T: Identify,
+    T::Id: Ord + Clone,
+    P: AsRef<str>,
+{
+    /// Sets the given texts for the node with the given id, replacing any previous ones.
+    pub fn insert(&mut self, node_id: T::Id, texts: Vec<P>) {
+        self.remove(&node_id);
+
+        let mut terms = BTreeSet::new();
+        let mut length = 0;
+        let mut offset = 0;
+        texts.iter().for_each(|text| {
+            let start = length;
+            tokenize(text.as_ref()).for_each(|token| {
+                self.postings
+                    .entry(token.term.clone())
+                    .or_default()
+                    .entry(node_id.clone())
+                    .or_default()
+                    .push(offset + token.position);
+
+                terms.insert(token.term);
+                length += 1;
+            });
+
+            // Texts are one position apart from each other, so phrases never span two texts.
+            offset += length - start + 1;
+        });
+
+        if length == 0 {
+            return;
+        }
+
+        self.total_length += length;
+        self.terms.insert(node_id.clone(), terms);
+        self.lengths.insert(node_id, length);
+    }
+}
+
+impl<T, P> SearchIndex<T, P>
+where
+    T: Identify,
+    T::Id: Ord,
+{
+    /// Removes all the texts of the node with the given id.
+    pub fn remove(&mut self, node_id: &T::Id) {
+        let Some(length) = self.lengths.remove(node_id) else {
+            return;
+        };
+
+        self.total_length -= length;
+        self.terms
+            .remove(node_id)
+            .unwrap_or_default()
+            .into_iter()
+            .for_each(|term| {
+                let Some(nodes) = self.postings.get_mut(&term) else {
+                    return;
+                };
+
+                nodes.remove(node_id);
+                if nodes.is_empty() {
+                    self.postings.remove(&term);
+                }
+            });
+    }
+
+    /// Returns the nodes matching all the clauses of the given query, sorted by relevance.
+    pub fn search<'a>(&'a self, query: &Query) -> Vec<Hit<'a, T::Id>> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut scores: Option<BTreeMap<&T::Id, f64>> = None;
+        for clause in query.clauses() {
+            let matches = self.matches(clause);
+            scores = Some(match scores {
+                None => matches,
+                Some(scores) => scores
+                    .into_iter()
+                    .filter_map(|(id, score)| Some((id, score + matches.get(id)?)))
+                    .collect(),
+            });
+        }
+
+        let mut hits: Vec<_> = scores
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(id, score)| Hit { id, score })
+            .collect();
+
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits
+    }
+
+    /// Returns the amount of indexed nodes.
+    pub fn len(&self) -> usize {
+        self.lengths.len()
+    }
+
+    /// Returns true if, and only if, the index has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.lengths.is_empty()
+    }
+
+    /// Returns the score of each node matching the given clause.
+    fn matches(&self, clause: &Clause) -> BTreeMap<&T::Id, f64> {
+        match clause {
+            Clause::Term(term) => self
+                .postings
+                .get_key_value(term)
+                .map(|(term, nodes)| self.scores(term, nodes.keys()))
+                .unwrap_or_default(),
+            Clause::Prefix(prefixes) => prefixes
+                .iter()
+                .flat_map(|prefix| {
+                    self.postings
+                        .range(prefix.clone()..)
+                        .take_while(|(term, _)| term.starts_with(prefix.as_str()))
+                })
+                // Terms matching several prefixes are scored once.
+                .collect::<BTreeMap<_, _>>()
+                .into_iter()
+                .fold(BTreeMap::new(), |mut scores, (term, nodes)| {
+                    self.scores(term, nodes.keys())
+                        .into_iter()
+                        .for_each(|(id, score)| *scores.entry(id).or_default() += score);
+                    scores
+                }),
+            Clause::Phrase(terms) => {
+                let Some(postings) = terms
+                    .iter()
+                    .map(|term| self.postings.get(term))
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    return BTreeMap::new();
+                };
+
+                let ids: BTreeSet<_> = postings[0]
+                    .iter()
+                    .filter(|(id, positions)| {
+                        positions.iter().any(|start| {
+                            postings.iter().enumerate().skip(1).all(|(offset, nodes)| {
+                                nodes.get(*id).is_some_and(|positions| {
+                                    positions.binary_search(&(start + offset)).is_ok()
+                                })
+                            })
+                        })
+                    })
+                    .map(|(id, _)| id)
+                    .collect();
+
+                terms.iter().fold(BTreeMap::new(), |mut scores, term| {
+                    self.scores(term, ids.iter().copied())
+                        .into_iter()
+                        .for_each(|(id, score)| *scores.entry(id).or_default() += score);
+                    scores
+                })
+            }
+        }
+    }
+
+    /// Returns the BM25 score of the given term for each of the given nodes.
+    fn scores<'a>(
+        &'a self,
+        term: &str,
+        ids: impl Iterator<Item = &'a T::Id>,
+    ) -> BTreeMap<&'a T::Id, f64> {
+        let Some(nodes) = self.postings.get(term) else {
+            return BTreeMap::new();
+        };
+
+        let total = self.lengths.len() as f64;
+        let frequency = nodes.len() as f64;
+        let idf = ((total - frequency + 0.5) / (frequency + 0.5) + 1.0).ln();
+        let average_length = self.total_length as f64 / total;
+
+        ids.filter_map(|id| {
+            let (id, positions) = nodes.get_key_value(id)?;
+            let tf = positions.len() as f64;
+            let length = self.lengths.get(id).copied().unwrap_or_default() as f64;
+            let norm = K1 * (1.0 - B + B * length / average_length);
+            Some((id, idf * tf * (K1 + 1.0) / (tf + norm)))
+        })
+        .collect()
+    }
+}
+
+/// Search indexes are serialized as their postings, since everything else can be derived from
+/// them.
+#[cfg(feature = "serde")]
+impl<T, P> serde::Serialize for SearchIndex<T, P>
+where
+    T: Identify,
+    T::Id: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.postings.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, P> serde::Deserialize<'de> for SearchIndex<T, P>
+where
+    T: Identify,
+    T::Id: Ord + Clone + serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let postings = BTreeMap::<String, BTreeMap<T::Id, Vec<usize>>>::deserialize(deserializer)?;
+
+        let mut index = Self::default();
+        postings.iter().for_each(|(term, nodes)| {
+            nodes.iter().for_each(|(node_id, positions)| {
+                index
+                    .terms
+                    .entry(node_id.clone())
+                    .or_default()
+                    .insert(term.clone());
+                *index.lengths.entry(node_id.clone()).or_default() += positions.len();
+                index.total_length += positions.len();
+            });
+        });
+
+        index.postings = postings;
+        Ok(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plotline::id::Identify;
+
+    use crate::query::Query;
+
+    use super::SearchIndex;
+
+    struct Node;
+
+    impl Identify for Node {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &0
+        }
+    }
+
+    fn index() -> SearchIndex<Node, &'static str> {
+        let mut index = SearchIndex::default();
+        index.insert(1, vec!["The quick brown fox jumps over the lazy dog."]);
+        index.insert(2, vec!["A lazy cat sleeps all day.", "Brown dogs bark."]);
+        index.insert(3, vec!["Dogs, dogs and more dogs: a dog lover's diary."]);
+        index
+    }
+
+    fn search(index: &SearchIndex<Node, &'static str>, query: &str) -> Vec<usize> {
+        index
+            .search(&Query::parse(query))
+            .into_iter()
+            .map(|hit| *hit.id)
+            .collect()
+    }
+
+    #[test]
+    fn terms_should_be_ranked() {
+        let index = index();
+
+        assert_eq!(
+            search(&index, "dog"),
+            vec![3, 1, 2],
+            "nodes with more occurrences should rank first"
+        );
+
+        assert_eq!(
+            search(&index, "lazy dogs"),
+            vec![1, 2],
+            "all terms should be matched"
+        );
+
+        assert!(search(&index, "").is_empty());
+        assert!(search(&index, "elephant").is_empty());
+    }
+
+    #[test]
+    fn phrases_should_be_matched_in_a_row() {
+        let index = index();
+
+        assert_eq!(search(&index, r#""lazy dog""#), vec![1]);
+        assert_eq!(search(&index, r#""brown dogs""#), vec![2]);
+        assert!(
+            search(&index, r#""day brown""#).is_empty(),
+            "phrases should not span multiple texts"
+        );
+    }
+
+    #[test]
+    fn prefixes_should_be_expanded() {
+        let index = index();
+
+        assert_eq!(search(&index, "sle*"), vec![2]);
+        assert_eq!(search(&index, "d*").len(), 3);
+    }
+
+    #[test]
+    fn prefixes_should_match_stemmed_terms() {
+        let mut index = index();
+        index.insert(4, vec!["A happy dog is running."]);
+
+        assert_eq!(
+            search(&index, "happy*"),
+            vec![4],
+            "words should match their stem even if it is shorter"
+        );
+        assert_eq!(search(&index, "running*"), vec![4]);
+        assert_eq!(search(&index, "happ*"), vec![4]);
+    }
+
+    #[test]
+    fn removed_nodes_should_not_be_matched() {
+        let mut index = index();
+        index.remove(&3);
+        index.insert(1, vec!["Nothing to see here."]);
+
+        assert_eq!(search(&index, "dog"), vec![2]);
+        assert_eq!(index.len(), 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialized_index_should_match_the_original() {
+        let original = index();
+        let json = serde_json::to_string(&original).expect("index should serialize");
+        let index: SearchIndex<Node, &'static str> =
+            serde_json::from_str(&json).expect("index should deserialize");
+
+        assert_eq!(index.len(), original.len());
+        assert_eq!(index.total_length, original.total_length);
+        assert_eq!(index.lengths, original.lengths);
+        assert_eq!(index.terms, original.terms);
+        assert_eq!(search(&index, "\"brown dog\""), vec![2]);
+    }
+}
@@ -0,0 +1,10 @@
+//! A full-text search index over the textual content of nodes.
+
+mod index;
+mod plugin;
+mod query;
+mod token;
+pub use index::{Hit, SearchIndex};
+pub use plugin::SearchPlugin;
+pub use query::{Clause, Query};
+pub use token::{tokenize, Token};
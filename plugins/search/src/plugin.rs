@@ -0,0 +1,203 @@
+//! The plugin implementation for [`SearchIndex`].
+
+use plotline::prelude::*;
+
+use crate::SearchIndex;
+
+/// Implements the [`Plugin`] trait for a full-text index over the texts of the property P in
+/// nodes of type T.
+///
+/// The index is available as a `Res<SearchIndex<T, P>>` resource. Plugins built from an existing
+/// search index start from it instead of an empty one.
+///
+/// The plugin is named `search` unless told otherwise, so installing more than one search index
+/// in the same schema requires naming them apart.
+pub struct SearchPlugin<T, P>
+where
+    T: Identify,
+{
+    name: &'static str,
+    index: SearchIndex<T, P>,
+}
+
+impl<T, P> Default for SearchPlugin<T, P>
+where
+    T: Identify,
+{
+    fn default() -> Self {
+        Self {
+            name: "search",
+            index: Default::default(),
+        }
+    }
+}
+
+impl<T, P> From<SearchIndex<T, P>> for SearchPlugin<T, P>
+where
+    T: Identify,
+{
+    fn from(index: SearchIndex<T, P>) -> Self {
+        Self {
+            index,
+            ..Default::default()
+        }
+    }
+}
+
+impl<T, P> SearchPlugin<T, P>
+where
+    T: Identify,
+{
+    /// Sets the name the plugin is installed with.
+    pub fn with_name(mut self, name: &'static str) -> Self {
+        self.name = name;
+        self
+    }
+}
+
+impl<T, P> SearchPlugin<T, P>
+where
+    T: 'static + Identify + Send + Sync,
+    T::Id: Ord + Clone + Send + Sync,
+    P: 'static + Property<T> + AsRef<str>,
+{
+    fn on_save(_: Ctx<T>, target: Target<T>, index: Res<SearchIndex<T, P>>) -> Result<()> {
+        let Some((node_id, texts)) = target.with(|node| (node.id().clone(), P::all(node))) else {
+            return Ok(());
+        };
+
+        index.with_mut(|index| index.insert(node_id, texts));
+        Ok(())
+    }
+
+    fn on_delete(_: Ctx<T>, target: Target<T>, index: Res<SearchIndex<T, P>>) -> Result<()> {
+        let Some(node_id) = target.with(|node| node.id().clone()) else {
+            return Ok(());
+        };
+
+        index.with_mut(|index| index.remove(&node_id));
+        Ok(())
+    }
+}
+
+impl<T, P> Plugin<T> for SearchPlugin<T, P>
+where
+    T: 'static + Identify + Send + Sync,
+    T::Id: Ord + Clone + Send + Sync,
+    P: 'static + Property<T> + AsRef<str>,
+{
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    fn install(self, schema: Schema<T>) -> Schema<T>
+    where
+        T: Identify,
+    {
+        schema
+            .with_resource(self.index)
+            .with_trigger(OnInstall, Self::on_save)
+            .with_trigger(AfterSave, Self::on_save)
+            .with_trigger(AfterDelete, Self::on_delete)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plotline::{
+        graph::Graph,
+        prelude::*,
+        schema::ops::{delete::Delete, save::Save},
+    };
+
+    use crate::{Query, SearchIndex, SearchPlugin};
+
+    #[derive(Debug, Clone)]
+    struct Node {
+        id: usize,
+        body: &'static str,
+    }
+
+    impl Identify for Node {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.id
+        }
+    }
+
+    struct Body(&'static str);
+
+    impl AsRef<str> for Body {
+        fn as_ref(&self) -> &str {
+            self.0
+        }
+    }
+
+    impl Property<Node> for Body {
+        fn all(source: &Node) -> Vec<Self> {
+            vec![Body(source.body)]
+        }
+    }
+
+    fn search(schema: &Schema<Node>, query: &str) -> Vec<usize> {
+        Res::<SearchIndex<Node, Body>>::from(schema.resources())
+            .with(|index| {
+                index
+                    .search(&Query::parse(query))
+                    .into_iter()
+                    .map(|hit| *hit.id)
+                    .collect()
+            })
+            .expect("index should exist")
+    }
+
+    #[test]
+    fn search_index_should_follow_schema_changes() {
+        let schema = Schema::from(Graph::from_iter([
+            Node {
+                id: 1,
+                body: "Plotting a novel",
+            },
+            Node {
+                id: 2,
+                body: "Writing characters",
+            },
+        ]))
+        .install(SearchPlugin::<Node, Body>::default())
+        .expect("plugin should be installed");
+
+        assert_eq!(
+            search(&schema, "novels"),
+            vec![1],
+            "existing nodes should be indexed"
+        );
+
+        Save::new(Node {
+            id: 2,
+            body: "Writing a novel about characters",
+        })
+        .execute(schema.transaction())
+        .expect("save transaction should not fail");
+
+        assert_eq!(
+            search(&schema, "novel").len(),
+            2,
+            "saved nodes should be indexed"
+        );
+
+        Delete::new(1)
+            .execute(schema.transaction())
+            .expect("delete transaction should not fail");
+
+        assert_eq!(
+            search(&schema, "novel"),
+            vec![2],
+            "deleted nodes should be removed"
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! The query definition.
+
+use crate::token::{normalize, tokenize};
+
+/// A condition every matching node must satisfy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Clause {
+    /// The node contains the given term.
+    Term(String),
+    /// The node contains a term starting with any of the given prefixes.
+    ///
+    /// Both the lowercased and the stemmed forms of a word are kept, since stemming may change
+    /// its ending (e.g. `happy` is indexed as `happi`).
+    Prefix(Vec<String>),
+    /// The node contains the given terms in a row.
+    Phrase(Vec<String>),
+}
+
+impl Clause {
+    /// Returns true if, and only if, the given term is matched by the clause.
+    fn matches(&self, term: &str) -> bool {
+        match self {
+            Clause::Term(expected) => term == expected,
+            Clause::Prefix(prefixes) => prefixes
+                .iter()
+                .any(|prefix| term.starts_with(prefix.as_str())),
+            Clause::Phrase(terms) => terms.first().is_some_and(|expected| term == expected),
+        }
+    }
+}
+
+/// A full-text query.
+///
+/// Words are matched by their stemmed form, words ending with `*` match any word starting with
+/// them, and words enclosed in double quotes must appear in a row.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Query {
+    clauses: Vec<Clause>,
+}
+
+impl From<&str> for Query {
+    fn from(query: &str) -> Self {
+        Self::parse(query)
+    }
+}
+
+impl Query {
+    /// Parses the given query.
+    pub fn parse(query: &str) -> Self {
+        let mut clauses = Vec::new();
+        for (index, part) in query.split('"').enumerate() {
+            // Odd parts are enclosed in double quotes.
+            if index % 2 == 1 {
+                let mut terms: Vec<_> = tokenize(part).map(|token| token.term).collect();
+                match terms.len() {
+                    0 => {}
+                    1 => clauses.extend(terms.pop().map(Clause::Term)),
+                    _ => clauses.push(Clause::Phrase(terms)),
+                }
+
+                continue;
+            }
+
+            part.split_whitespace().for_each(|word| {
+                let Some(prefix) = word.strip_suffix('*') else {
+                    clauses.extend(tokenize(word).map(|token| Clause::Term(token.term)));
+                    return;
+                };
+
+                let words: Vec<_> = prefix
+                    .split(|c: char| !c.is_alphanumeric())
+                    .filter(|word| !word.is_empty())
+                    .collect();
+
+                if let Some((last, rest)) = words.split_last() {
+                    clauses.extend(rest.iter().map(|word| Clause::Term(normalize(word))));
+                    let mut prefixes = vec![last.to_lowercase(), normalize(last)];
+                    prefixes.dedup();
+                    clauses.push(Clause::Prefix(prefixes));
+                }
+            });
+        }
+
+        Self { clauses }
+    }
+
+    /// Returns the clauses of the query.
+    pub fn clauses(&self) -> &[Clause] {
+        &self.clauses
+    }
+
+    /// Returns true if, and only if, the query has no clauses.
+    pub fn is_empty(&self) -> bool {
+        self.clauses.is_empty()
+    }
+
+    /// Returns an excerpt of about the given amount of characters surrounding the first match of
+    /// the query in the given text, if any.
+    pub fn snippet(&self, text: &str, width: usize) -> Option<String> {
+        let token = tokenize(text).find(|token| {
+            self.clauses
+                .iter()
+                .any(|clause| clause.matches(&token.term))
+        })?;
+
+        let before = text[..token.offset.start]
+            .char_indices()
+            .rev()
+            .nth(width / 2)
+            .map(|(index, _)| index)
+            .unwrap_or_default();
+
+        let after = text[before..]
+            .char_indices()
+            .nth(width)
+            .map(|(index, _)| before + index)
+            .unwrap_or(text.len());
+
+        // Words cut by the boundaries of the excerpt are left out.
+        let mut words: Vec<_> = text[before..after].split_whitespace().collect();
+        if text[..before].ends_with(|c: char| !c.is_whitespace()) && words.len() > 1 {
+            words.remove(0);
+        }
+
+        if text[after..].starts_with(|c: char| !c.is_whitespace()) && words.len() > 1 {
+            words.pop();
+        }
+
+        let mut snippet = words.join(" ");
+        if before > 0 {
+            snippet.insert(0, '…');
+        }
+
+        if after < text.len() {
+            snippet.push('…');
+        }
+
+        Some(snippet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Clause, Query};
+
+    #[test]
+    fn query_should_be_parsed() {
+        let query = Query::parse(r#"Running "black cats" dog* "alone""#);
+
+        assert_eq!(
+            query.clauses(),
+            &[
+                Clause::Term("run".into()),
+                Clause::Phrase(vec!["black".into(), "cat".into()]),
+                Clause::Prefix(vec!["dog".into()]),
+                Clause::Term("alon".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn prefixes_should_keep_the_stemmed_form() {
+        assert_eq!(
+            Query::parse("happy*").clauses(),
+            &[Clause::Prefix(vec!["happy".into(), "happi".into()])]
+        );
+    }
+
+    #[test]
+    fn snippet_should_surround_first_match() {
+        let query = Query::parse("cats");
+        let text = "Dogs are loyal.\nCats are independent and curious animals.";
+
+        assert_eq!(
+            query.snippet(text, 20).as_deref(),
+            Some("…are loyal. Cats…")
+        );
+
+        assert_eq!(Query::parse("bird").snippet(text, 20), None);
+    }
+}
@@ -0,0 +1,80 @@
+//! Text tokenization.
+
+use std::{ops::Range, sync::LazyLock};
+
+use rust_stemmers::{Algorithm, Stemmer};
+
+static STEMMER: LazyLock<Stemmer> = LazyLock::new(|| Stemmer::create(Algorithm::English));
+
+/// A word of a text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// The normalized form of the word.
+    pub term: String,
+    /// The position of the word in the text, counted in words.
+    pub position: usize,
+    /// The byte range of the word in the text.
+    pub offset: Range<usize>,
+}
+
+/// Returns the lowercased and stemmed form of the given word.
+pub(crate) fn normalize(word: &str) -> String {
+    STEMMER.stem(&word.to_lowercase()).into_owned()
+}
+
+/// Returns an iterator over the words of the given text.
+///
+/// Words are maximal runs of alphanumeric characters, normalized by lowercasing and English
+/// stemming.
+pub fn tokenize(text: &str) -> impl Iterator<Item = Token> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .enumerate()
+        .map(move |(position, word)| {
+            // Words are subslices of the text, so their offset is the distance between pointers.
+            let start = word.as_ptr() as usize - text.as_ptr() as usize;
+
+            Token {
+                term: normalize(word),
+                position,
+                offset: start..start + word.len(),
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::tokenize;
+
+    #[test]
+    fn words_should_be_normalized() {
+        let tokens: Vec<_> = tokenize("The Running dogs, jumped!").collect();
+
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|token| token.term.as_str())
+                .collect::<Vec<_>>(),
+            vec!["the", "run", "dog", "jump"],
+            "words should be lowercased and stemmed"
+        );
+
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|token| token.position)
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2, 3],
+            "positions should be counted in words"
+        );
+
+        assert_eq!(tokens[1].offset, 4..11, "offsets should be in bytes");
+        assert_eq!(tokens[3].offset, 18..24, "last word should be included");
+    }
+
+    #[test]
+    fn unicode_words_should_be_tokenized() {
+        let terms: Vec<_> = tokenize("déjà vu").map(|token| token.offset).collect();
+        assert_eq!(terms, vec![0..6, 7..9]);
+    }
+}